@@ -0,0 +1,56 @@
+/// Semilla que se usa si no se indica otra, o si la indicada es 0.
+pub const SEMILLA_POR_DEFECTO: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// Generador de numeros pseudoaleatorios xorshift64.
+/// Con la misma semilla produce siempre la misma secuencia, asi una partida se puede repetir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Azar {
+    pub estado: u64,
+}
+
+impl Azar {
+    /// Crea un generador. xorshift no funciona con estado 0, asi que en ese caso usa SEMILLA_POR_DEFECTO.
+    pub fn crear(semilla: u64) -> Azar {
+        Azar {
+            estado: if semilla == 0 {
+                SEMILLA_POR_DEFECTO
+            } else {
+                semilla
+            },
+        }
+    }
+
+    /// Devuelve el siguiente numero de la secuencia.
+    pub fn siguiente(&mut self) -> u64 {
+        let mut x = self.estado;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.estado = x;
+        x
+    }
+
+    /// Devuelve un indice entre 0 y largo - 1. largo debe ser mayor a 0.
+    pub fn indice(&mut self, largo: usize) -> usize {
+        (self.siguiente() % largo as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_misma_semilla_misma_secuencia() {
+        let mut a = Azar::crear(7);
+        let mut b = Azar::crear(7);
+        let secuencia: Vec<u64> = (0..5).map(|_| a.siguiente()).collect();
+        assert_eq!(
+            secuencia,
+            (0..5).map(|_| b.siguiente()).collect::<Vec<u64>>()
+        );
+        assert_ne!(secuencia[0], secuencia[1]);
+        assert_eq!(Azar::crear(0), Azar::crear(SEMILLA_POR_DEFECTO));
+        assert!((0..100).all(|_| a.indice(3) < 3));
+    }
+}
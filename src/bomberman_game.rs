@@ -1,16 +1,18 @@
-use crate::modelo::constantes::{
-    BOMBA_ESPECIAL, BOMBA_NORMAL, DESVIO, ENEMIGO, PARED, PIEDRA, VACIO,
-};
-use crate::modelo::fabrica::crear_pieza;
+use crate::cli::{self, ModoColor};
+use crate::dibujo;
+use crate::json;
+use crate::modelo::error::ParseError;
+use crate::modelo::evento::Evento;
 use crate::modelo::mapa::Mapa;
-use crate::modelo::tile::Tile;
+use crate::partida::Partida;
 use crate::turno;
-use std::io::{self, Write};
+use std::collections::HashSet;
+use std::io::{self, Read, Write};
 use std::path::PathBuf;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::{fs::File, io::BufReader};
+
+/// Flag de linea de comando que indica una posicion a detonar.
+pub const FLAG_DETONAR: &str = "--detonar";
 
 /// Lee un archivo y devuelve un mapa listo para jugar.
 /// Si el archivo no existe o tiene el formato incorrecto, devuelve un error
@@ -38,91 +40,116 @@ pub fn inicializar_output_dir(argumentos: &[String]) -> Result<File, String> {
     }
 }
 
-/// Parsea los valores dados por linea de comando, devuelve una tupla si se pudo parsear.
-fn inicializar_posicion(argumentos: &[String]) -> Result<(usize, usize), String> {
-    let x_pos = match argumentos[3].parse::<usize>() {
-        Err(why) => {
-            return Err(format!("No se pudo parsear el argumento x: {}", why));
+/// Parsea una coordenada del mapa, devuelve el valor si se pudo parsear.
+/// nombre se usa para indicar en el error que argumento fallo.
+fn parsear_coordenada(valor: &str, nombre: &str) -> Result<usize, String> {
+    match valor.trim().parse::<usize>() {
+        Err(why) => Err(format!(
+            "No se pudo parsear el argumento {}: {}",
+            nombre, why
+        )),
+        Ok(valor) => Ok(valor),
+    }
+}
+
+/// Parsea una posicion con el formato x,y. Ejemplo: 4,2.
+pub fn parsear_posicion(posicion: &str) -> Result<(usize, usize), String> {
+    match posicion.split_once(',') {
+        Some((x, y)) => Ok((parsear_coordenada(x, "x")?, parsear_coordenada(y, "y")?)),
+        None => Err(format!(
+            "La posicion {} debe tener el formato x,y",
+            posicion
+        )),
+    }
+}
+
+/// Parsea las posiciones a detonar dadas por linea de comando, en el orden en el que se deben detonar.
+/// Acepta dos formatos a partir del tercer argumento:
+///   - x y: una unica detonacion.
+///   - --detonar x,y [--detonar x,y ...]: una o mas detonaciones.
+fn inicializar_posiciones(argumentos: &[String]) -> Result<Vec<(usize, usize)>, String> {
+    let resto = &argumentos[3..];
+    if !resto.iter().any(|argumento| argumento == FLAG_DETONAR) {
+        if resto.len() != 2 {
+            return Err("Se esperaban las coordenadas x y".to_string());
         }
-        Ok(x_pos) => x_pos,
-    };
-    let y_pos = match argumentos[4].parse::<usize>() {
-        Err(why) => {
-            return Err(format!("No se pudo parsear el argumento y: {}", why));
+        let x_pos = parsear_coordenada(&resto[0], "x")?;
+        let y_pos = parsear_coordenada(&resto[1], "y")?;
+        return Ok(vec![(x_pos, y_pos)]);
+    }
+
+    let mut posiciones = Vec::new();
+    let mut argumentos = resto.iter();
+    while let Some(argumento) = argumentos.next() {
+        if argumento != FLAG_DETONAR {
+            return Err(format!("Argumento inesperado: {}", argumento));
+        }
+        match argumentos.next() {
+            Some(posicion) => posiciones.push(parsear_posicion(posicion)?),
+            None => return Err(format!("Falta la posicion despues de {}", FLAG_DETONAR)),
         }
-        Ok(y_pos) => y_pos,
-    };
-    Ok((x_pos, y_pos))
+    }
+    Ok(posiciones)
+}
+
+/// Detona la bomba de la posicion dada, que es la detonacion numero de una secuencia que empieza en 1.
+/// Si no hay bomba en la posicion, devuelve un error indicando cual fue la detonacion que fallo.
+/// Devuelve los eventos de la detonacion.
+pub fn detonar_posicion(
+    mapa: &mut Mapa,
+    (x_pos, y_pos): (usize, usize),
+    numero: usize,
+) -> Result<Vec<Evento>, String> {
+    turno::jugar_turno_con_eventos(mapa, x_pos, y_pos).map_err(|_| {
+        format!(
+            "No hay bomba en la posicion ({}, {}) al momento de la detonacion {}",
+            x_pos, y_pos, numero
+        )
+    })
 }
 
-/// Juega un turno del juego a partir del archivo dado como input.
-/// Devuelve el mapa resultante del turno.
+/// Detona, en orden, las bombas que se encuentran en cada una de las posiciones.
+/// Si en alguna posicion no hay bomba al momento de detonarla, devuelve un error indicando cual fue.
+/// Las detonaciones anteriores a la que fallo quedan aplicadas en el mapa.
+/// Devuelve los eventos de la ultima detonacion.
+pub fn detonar_en_orden(
+    mapa: &mut Mapa,
+    posiciones: &[(usize, usize)],
+) -> Result<Vec<Evento>, String> {
+    let mut eventos = Vec::new();
+    for (numero, posicion) in posiciones.iter().enumerate() {
+        eventos = detonar_posicion(mapa, *posicion, numero + 1)?;
+    }
+    Ok(eventos)
+}
+
+/// Juega los turnos del juego a partir del archivo dado como input.
+/// Devuelve el mapa resultante despues de todas las detonaciones.
 /// Si el archivo no existe o tiene el formato incorrecto, devuelve un error.
 pub fn jugar(argumentos: &[String]) -> Result<Mapa, String> {
     let mut mapa = inicializar_mapa(&argumentos[1])?;
-    let (x_pos, y_pos) = inicializar_posicion(argumentos)?;
-    turno::jugar_turno(&mut mapa, x_pos, y_pos)?;
+    let posiciones = inicializar_posiciones(argumentos)?;
+    detonar_en_orden(&mut mapa, &posiciones)?;
     Ok(mapa)
 }
 
-/// Lee el archivo de texto en la ruta especificada y devuelve un iterador de lineas.
+/// Abre el archivo de texto en la ruta especificada para leerlo.
 /// Si no se pudo abrir el archivo, devuelve un error.
-fn read_file(path: &str) -> Result<io::Lines<BufReader<File>>, &str> {
+fn read_file(path: &str) -> Result<BufReader<File>, ParseError> {
     match File::open(path) {
-        Ok(file) => Ok(BufReader::new(file).lines()),
-        Err(_) => Err("No se pudo abrir el archivo"),
-    }
-}
-
-/// Transforma una linea de texto en un vector de tiles.
-/// Para poder ser transformada, toda la linea tiene que venir con el formato correcto:
-/// <tipo<numero> <tipo><numero> <tipo><numero>
-/// Si no se pudo transformar la linea, devuelve un error.
-fn transformar_linea(s: String, y_pos: usize) -> Result<Vec<Tile>, String> {
-    let caracteres: Vec<&str> = s.trim().split(' ').filter(|x| !x.is_empty()).collect();
-    let mut tiles: Vec<Tile> = Vec::new();
-
-    for (x_pos, caracter) in caracteres.into_iter().enumerate() {
-        let tile = crear_pieza(caracter, x_pos, y_pos);
-        match tile {
-            Ok(tile) => tiles.push(tile),
-            Err(why) => return Err(why),
-        }
+        Ok(file) => Ok(BufReader::new(file)),
+        Err(why) => Err(ParseError::Archivo {
+            path: path.to_string(),
+            mensaje: why.to_string(),
+        }),
     }
-    Ok(tiles)
 }
 
 /// Transforma un archivo de texto en un mapa.
-/// Si no se pudo transformar el archivo, devuelve un error.
-pub fn transformar_a_mapa(path: &str) -> Result<Mapa, String> {
-    let lineas = read_file(path)?;
-    let mut mapa = Mapa::crear();
-    let mut alto: usize = 0;
-
-    for (y_pos, linea) in lineas.into_iter().enumerate() {
-        match linea {
-            Err(_) => return Err("No se pudo leer la linea".to_string()),
-            Ok(linea) => {
-                let tiles_temp = transformar_linea(linea, y_pos)?;
-                if mapa.side_size == 0 {
-                    mapa.side_size = tiles_temp.len();
-                }
-
-                if tiles_temp.len() != mapa.side_size {
-                    return Err("El mapa no es cuadrado".to_string());
-                }
-
-                mapa.tiles.push(tiles_temp);
-            }
-        }
-        alto = y_pos;
-    }
-
-    if mapa.side_size != alto + 1 {
-        return Err("El mapa no es cuadrado".to_string());
-    }
-
-    Ok(mapa)
+/// Todas las filas deben tener la misma cantidad de columnas, pero el mapa no necesita ser cuadrado.
+/// Si no se pudo transformar el archivo, devuelve un error indicando en que fila y columna ocurrio.
+pub fn transformar_a_mapa(path: &str) -> Result<Mapa, ParseError> {
+    Mapa::from_reader(read_file(path)?)
 }
 
 /// Abre un archivo en la ruta especificada.
@@ -149,35 +176,54 @@ fn open_path(carpeta: &str, filename: &str) -> io::Result<File> {
     }
 }
 
-/// Imprime el mapa en un archivo de texto.
-/// Los caracteres usados para representar cada tile estan puestos aca para separarlos del modelo.
-pub fn print_mapa_to_file(mapa: &Mapa, file: &mut File) {
-    let mut string: String = String::new();
-    for v in mapa.tiles.iter() {
-        for t in v.iter() {
-            match t {
-                Tile::Enemigo(enemigo) => {
-                    string.push_str(format!("{}{}", ENEMIGO, enemigo.vida).as_str())
-                }
-                Tile::BombaNormal(bomba) => {
-                    string.push_str(format!("{}{}", BOMBA_NORMAL, bomba.radio).as_str())
-                }
-                Tile::BombaEspecial(bomba) => {
-                    string.push_str(format!("{}{}", BOMBA_ESPECIAL, bomba.radio).as_str())
-                }
-                Tile::Piedra(_) => string.push(PIEDRA),
-                Tile::Pared(_) => string.push(PARED),
-                Tile::Desvio(desvio) => {
-                    string.push_str(format!("{}{}", DESVIO, desvio.char_direccion()).as_str())
-                }
-                Tile::Vacio => string.push(VACIO),
-            }
-            string.push(' ');
-        }
-        string.push('\n');
+/// Imprime el mapa en un archivo de texto, en el formato canonico de los archivos de mapa.
+/// Los enemigos se escriben con las bombas que ya los impactaron y las patrullas con su sentido, y el jugador indica si pone bombas especiales,
+/// asi un mapa a medio jugar se carga igual que como estaba.
+/// Devuelve el error si no se pudo escribir.
+pub fn print_mapa_to_file(mapa: &Mapa, file: &mut File) -> io::Result<()> {
+    mapa.write_to(file)
+}
+
+/// Guarda el mapa en un archivo en JSON, incluidas las bombas que ya impactaron a cada enemigo y las mechas.
+/// Cargado con cargar_mapa, el mapa se comporta igual que el original.
+pub fn guardar_mapa(mapa: &Mapa, file: &mut File) -> io::Result<()> {
+    file.write_all(json::mapa_a_json(mapa).as_bytes())
+}
+
+/// Guarda en un archivo todo el estado de la partida en JSON: el mapa, el tick, el jugador y el generador de azar.
+pub fn guardar_partida(partida: &Partida, file: &mut File) -> io::Result<()> {
+    file.write_all(json::partida_a_json(partida).as_bytes())
+}
+
+/// Lee el archivo completo en la ruta especificada.
+fn leer_texto(path: &str) -> Result<String, ParseError> {
+    let mut texto = String::new();
+    match read_file(path)?.read_to_string(&mut texto) {
+        Ok(_) => Ok(texto),
+        Err(why) => Err(ParseError::Archivo {
+            path: path.to_string(),
+            mensaje: why.to_string(),
+        }),
+    }
+}
+
+/// Transforma un texto en un mapa, detectando si esta en JSON o en formato texto.
+pub fn mapa_desde_texto(texto: &str) -> Result<Mapa, ParseError> {
+    if json::es_json(texto) {
+        json::mapa_desde_json(texto)
+    } else {
+        texto.parse()
     }
+}
+
+/// Carga un mapa guardado con guardar_mapa o con print_mapa_to_file, con el formato que detecta mapa_desde_texto.
+pub fn cargar_mapa(path: &str) -> Result<Mapa, ParseError> {
+    mapa_desde_texto(&leer_texto(path)?)
+}
 
-    let _ = file.write(string.as_bytes());
+/// Carga una partida guardada con guardar_partida. Sigue desde el mismo tick y con el mismo estado que la original.
+pub fn cargar_partida(path: &str) -> Result<Partida, ParseError> {
+    json::partida_desde_json(&leer_texto(path)?)
 }
 
 /// Imprime un error en un archivo de texto.
@@ -188,52 +234,31 @@ pub fn print_err_to_file(err: String, mut file: File) -> std::io::Result<()> {
 }
 
 /// Imprime el mapa en la consola.
-pub fn print_mapa_debug(mapa: &Mapa) {
-    for v in mapa.tiles.iter() {
-        for t in v.iter() {
-            match t {
-                Tile::Enemigo(enemigo) => print!("F{}", enemigo.vida),
-                Tile::BombaNormal(bomba) => print!("B{}", bomba.radio),
-                Tile::BombaEspecial(bomba) => print!("S{}", bomba.radio),
-                Tile::Piedra(_) => print!("R"),
-                Tile::Pared(_) => print!("W"),
-                Tile::Desvio(desvio) => print!("D{}", desvio.char_direccion()),
-                Tile::Vacio => print!("_"),
-            }
-            print!(" ");
-        }
-        println!()
+/// Si el modo de color lo indica lo dibuja con colores, si no lo imprime en el formato de los archivos de mapa.
+/// Decide igual que la opcion --color de la linea de comandos.
+pub fn print_mapa_debug(mapa: &Mapa, color: ModoColor) {
+    if cli::usar_color(color) {
+        print!("{}", dibujo::dibujar(mapa, &HashSet::new()));
+    } else {
+        print!("{}", mapa);
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::modelo::error::TipoError;
+    use crate::modelo::tile::Tile;
     use crate::modelo::{bomba::Bomba, enemigo::Enemigo, obstaculo::Obstaculo};
 
     use super::*;
 
-    #[test]
-    fn test_transformar_linea() {
-        let linea = "F1 _ _ B3 _ R W".to_string();
-        let tiles = transformar_linea(linea, 0);
-        assert_eq!(tiles.is_ok(), true);
-        let tiles = tiles.unwrap();
-        assert_eq!(tiles.len(), 7);
-        assert_eq!(tiles[0], Tile::Enemigo(Enemigo::crear(0, 0, 1)));
-        assert_eq!(tiles[1], Tile::Vacio);
-        assert_eq!(tiles[2], Tile::Vacio);
-        assert_eq!(tiles[3], Tile::BombaNormal(Bomba::crear(3, 0, 3, false)));
-        assert_eq!(tiles[4], Tile::Vacio);
-        assert_eq!(tiles[5], Tile::Piedra(Obstaculo::crear(5, 0, false)));
-        assert_eq!(tiles[6], Tile::Pared(Obstaculo::crear(6, 0, true)));
-    }
-
     #[test]
     fn test_transformar_a_mapa() {
         let mapa = transformar_a_mapa("mapas/mapa_test_transformar.txt");
-        assert_eq!(mapa.is_ok(), true);
+        assert!(mapa.is_ok());
         let mapa = mapa.unwrap();
-        assert_eq!(mapa.side_size, 7);
+        assert_eq!(mapa.ancho, 7);
+        assert_eq!(mapa.alto, 7);
         assert_eq!(mapa.tiles[0][0], Tile::Enemigo(Enemigo::crear(0, 0, 1)));
         assert_eq!(mapa.tiles[0][1], Tile::Vacio);
         assert_eq!(mapa.tiles[0][2], Tile::Vacio);
@@ -253,26 +278,190 @@ mod test {
     fn test_print_mapa_to_file() {
         let mapa = transformar_a_mapa("mapas/mapa_test_crear.txt").unwrap();
         let mut file = open_path("mapas", "mapa_test_guardar.txt").unwrap();
-        let _ = print_mapa_to_file(&mapa, &mut file);
+        print_mapa_to_file(&mapa, &mut file).unwrap();
         let mapa2 = transformar_a_mapa("mapas/mapa_test_guardar.txt").unwrap();
         assert_eq!(mapa, mapa2);
     }
 
+    #[test]
+    fn test_print_mapa_to_file_numeros_de_varios_digitos() {
+        let mapa = transformar_a_mapa("mapas/mapa_test_varios_digitos.txt").unwrap();
+        let mut file = open_path("mapas", "mapa_test_varios_digitos_guardar.txt").unwrap();
+        print_mapa_to_file(&mapa, &mut file).unwrap();
+        let mapa2 = transformar_a_mapa("mapas/mapa_test_varios_digitos_guardar.txt").unwrap();
+        assert_eq!(mapa, mapa2);
+        assert_eq!(mapa.tiles[0][0], Tile::Enemigo(Enemigo::crear(0, 0, 12)));
+        assert_eq!(
+            mapa.tiles[0][2],
+            Tile::BombaNormal(Bomba::crear(2, 0, 15, false))
+        );
+    }
+
+    #[test]
+    fn test_guardar_mapa_conserva_los_impactos() {
+        let mut mapa: Mapa = "F3 B1\nB1 S1m4".parse().unwrap();
+        turno::jugar_turno(&mut mapa, 1, 0).unwrap();
+
+        let mut file = open_path("mapas", "mapa_test_guardar_impactos.json").unwrap();
+        guardar_mapa(&mapa, &mut file).unwrap();
+        assert_eq!(
+            cargar_mapa("mapas/mapa_test_guardar_impactos.json"),
+            Ok(mapa.clone())
+        );
+
+        let mut file = open_path("mapas", "mapa_test_guardar_impactos.txt").unwrap();
+        print_mapa_to_file(&mapa, &mut file).unwrap();
+        assert_eq!(
+            cargar_mapa("mapas/mapa_test_guardar_impactos.txt"),
+            Ok(mapa)
+        );
+    }
+
+    #[test]
+    fn test_guardar_y_cargar_partida() {
+        let mapa = "J _ F2\n_ B1m2 _".parse().unwrap();
+        let mut partida = Partida::crear(mapa, crate::partida::ModoCadena::Instantanea);
+        partida.poner_bomba().unwrap();
+        partida.avanzar();
+
+        let mut file = open_path("mapas", "partida_test_guardar.json").unwrap();
+        guardar_partida(&partida, &mut file).unwrap();
+        let mut cargada = cargar_partida("mapas/partida_test_guardar.json").unwrap();
+        assert_eq!(cargada, partida);
+        assert_eq!(
+            cargada.avanzar_hasta_apagarse(),
+            partida.avanzar_hasta_apagarse()
+        );
+        assert_eq!(cargada, partida);
+
+        assert!(matches!(
+            cargar_partida("mapas/mapa_test_crear.txt"),
+            Err(ParseError::Json { .. })
+        ));
+        assert!(matches!(
+            cargar_partida("mapas/no_existe.json"),
+            Err(ParseError::Archivo { .. })
+        ));
+    }
+
     #[test]
     fn test_abre_directorio_existente() {
         let file = open_path("mapas", "mapa_test_guardar.txt");
-        assert_eq!(file.is_ok(), true);
+        assert!(file.is_ok());
     }
 
     #[test]
     fn test_no_abre_directorio_no_existente() {
         let file = open_path("no_mapas", "mapa1.txt");
-        assert_eq!(file.is_err(), true);
+        assert!(file.is_err());
     }
 
     #[test]
     fn test_mapa_no_cuadrado() {
-        let mapa = transformar_a_mapa("mapas/mapa_test_no_cuadrado.txt");
-        assert_eq!(mapa.is_err(), true);
+        let mapa = transformar_a_mapa("mapas/mapa_no_cuadrado.txt");
+        assert!(mapa.is_ok());
+        let mapa = mapa.unwrap();
+        assert_eq!(mapa.ancho, 5);
+        assert_eq!(mapa.alto, 3);
+        assert_eq!(mapa.tiles[2][4], Tile::Vacio);
+    }
+
+    #[test]
+    fn test_mapa_filas_irregulares() {
+        let mapa = transformar_a_mapa("mapas/mapa_test_filas_irregulares.txt");
+        assert_eq!(
+            mapa,
+            Err(ParseError::FilaIrregular {
+                fila: 1,
+                esperadas: 5,
+                encontradas: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_mapa_token_invalido() {
+        let mapa = transformar_a_mapa("mapas/mapa_test_token_invalido.txt");
+        let error = mapa.unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::Token {
+                fila: 2,
+                columna: 2,
+                token: "X1".to_string(),
+                tipo: TipoError::PiezaDesconocida
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "fila 2, columna 2: >X1<: pieza desconocida"
+        );
+    }
+
+    #[test]
+    fn test_mapa_archivo_inexistente() {
+        let mapa = transformar_a_mapa("mapas/no_existe.txt");
+        assert!(matches!(mapa, Err(ParseError::Archivo { .. })));
+    }
+
+    fn argumentos(valores: &[&str]) -> Vec<String> {
+        valores.iter().map(|valor| valor.to_string()).collect()
+    }
+
+    #[test]
+    fn test_inicializar_posiciones() {
+        let posiciones = inicializar_posiciones(&argumentos(&["bin", "mapa", "out", "1", "2"]));
+        assert_eq!(posiciones, Ok(vec![(1, 2)]));
+
+        let posiciones = inicializar_posiciones(&argumentos(&[
+            "bin",
+            "mapa",
+            "out",
+            "--detonar",
+            "0,0",
+            "--detonar",
+            "4,2",
+        ]));
+        assert_eq!(posiciones, Ok(vec![(0, 0), (4, 2)]));
+
+        let posiciones =
+            inicializar_posiciones(&argumentos(&["bin", "mapa", "out", "--detonar", "4"]));
+        assert!(posiciones.is_err());
+
+        let posiciones = inicializar_posiciones(&argumentos(&["bin", "mapa", "out", "--detonar"]));
+        assert!(posiciones.is_err());
+    }
+
+    #[test]
+    fn test_jugar_varias_detonaciones() {
+        let mapa = jugar(&argumentos(&[
+            "bin",
+            "mapas/mapa_2.txt",
+            "out",
+            "--detonar",
+            "2,4",
+            "--detonar",
+            "6,6",
+        ]));
+        let mut resultado_deseado = transformar_a_mapa("mapas/mapa_2_deseado.txt").unwrap();
+        resultado_deseado.destruir_tile(6, 6);
+        assert_eq!(mapa, Ok(resultado_deseado));
+    }
+
+    #[test]
+    fn test_jugar_detonacion_sin_bomba() {
+        let mapa = jugar(&argumentos(&[
+            "bin",
+            "mapas/mapa_2.txt",
+            "out",
+            "--detonar",
+            "2,4",
+            "--detonar",
+            "2,0",
+        ]));
+        assert_eq!(
+            mapa,
+            Err("No hay bomba en la posicion (2, 0) al momento de la detonacion 2".to_string())
+        );
     }
 }
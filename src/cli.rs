@@ -0,0 +1,859 @@
+use crate::bomberman_game::{
+    self, detonar_en_orden, detonar_posicion, parsear_posicion, print_err_to_file,
+    print_mapa_to_file, FLAG_DETONAR,
+};
+use crate::cobertura;
+use crate::dibujo;
+use crate::grafo;
+use crate::json;
+use crate::modelo::error::ParseError;
+use crate::modelo::mapa::Mapa;
+use crate::modelo::tile::Tile;
+use crate::solver;
+use crate::svg;
+use crate::validador::{self, Severidad};
+use std::fs::File;
+use std::io::{IsTerminal, Read, Write};
+
+/// El comando termino correctamente.
+pub const EXIT_OK: i32 = 0;
+/// El comando fallo al jugar o al leer o escribir un archivo.
+pub const EXIT_ERROR: i32 = 1;
+/// Los argumentos no son validos.
+pub const EXIT_USO_INCORRECTO: i32 = 2;
+/// El mapa no se pudo transformar o tiene errores de validacion.
+pub const EXIT_MAPA_INVALIDO: i32 = 3;
+
+/// Nombres aceptados como primer argumento. Cualquier otro se interpreta como la forma original del programa.
+const COMANDOS: [&str; 11] = [
+    "play", "validate", "render", "solve", "stats", "heatmap", "graph", "svg", "help", "--help",
+    "-h",
+];
+
+/// Argumento que indica que el mapa se lee de la entrada estandar o se escribe en la salida estandar.
+const ESTANDAR: &str = "-";
+
+const AYUDA: &str = "Uso: bomberman <comando> [opciones]
+
+Comandos:
+  play <mapa> (--detonar x,y)... [--salida <archivo>] [--formato texto|json]
+      Detona en orden las bombas indicadas y escribe el mapa resultante.
+      Con --formato json el mapa se escribe en JSON, incluyendo las bombas que ya impactaron a cada enemigo.
+  validate <mapa> [--estricto]
+      Verifica que el mapa tenga el formato correcto y busca problemas de diseño:
+      desvios hacia paredes, fuera del mapa o en ciclo, enemigos inalcanzables,
+      bombas que no alcanzan nada y mapas sin bombas.
+      Falla si encuentra errores, o tambien advertencias con --estricto.
+  render <mapa> [--formato texto|json] [--color auto|always|never] [--detonar x,y]...
+      Muestra el mapa. Con --formato json lo convierte a JSON.
+      Con colores, dibuja el tablero con los desvios como flechas y los enemigos coloreados segun su vida.
+      Por defecto usa colores solo si la salida es una terminal y NO_COLOR no esta definida.
+      Con --detonar, detona antes las bombas indicadas y resalta las casillas alcanzadas por la ultima.
+  solve <mapa>
+      Busca la menor cantidad de detonaciones que eliminan a todos los enemigos.
+  stats <mapa>
+      Muestra la cantidad de cada tipo de tile del mapa.
+  heatmap <mapa> [--ansi]
+      Muestra cuantas bombas alcanzan cada casilla, contando desvios y reacciones en cadena.
+      Con --ansi colorea cada casilla segun su cobertura.
+  graph <mapa> [--formato dot|json]
+      Exporta el grafo de reacciones en cadena: que bomba hace detonar a cual. Por defecto en DOT.
+  svg <mapa> [--detonar x,y]... [--salida <archivo>]
+      Exporta el mapa como imagen SVG. Con --detonar, detona en orden las bombas indicadas
+      y dibuja el mapa previo a la ultima detonacion con el recorrido de esa explosion, incluidos los desvios.
+  help
+      Muestra esta ayuda.
+
+<mapa> puede ser - para leer el mapa de la entrada estandar.
+El mapa puede estar en formato texto o JSON; el formato se detecta automaticamente.
+--salida puede ser - para escribir en la salida estandar, que es lo que se hace si no se indica.
+
+Tambien se acepta la forma original:
+  bomberman maze.txt /path/to/output_dir/ x y
+  bomberman maze.txt /path/to/output_dir/ --detonar x,y [--detonar x,y ...]
+
+Codigos de salida: 0 ok, 1 error al jugar o de entrada/salida, 2 uso incorrecto,
+3 mapa invalido o con errores de validacion.";
+
+/// Comando pedido por linea de comando.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comando {
+    Jugar {
+        mapa: String,
+        posiciones: Vec<(usize, usize)>,
+        salida: String,
+        formato: FormatoMapa,
+    },
+    Validar {
+        mapa: String,
+        estricto: bool,
+    },
+    Renderizar {
+        mapa: String,
+        formato: FormatoMapa,
+        color: ModoColor,
+        posiciones: Vec<(usize, usize)>,
+    },
+    Resolver {
+        mapa: String,
+    },
+    Estadisticas {
+        mapa: String,
+    },
+    Cobertura {
+        mapa: String,
+        ansi: bool,
+    },
+    Grafo {
+        mapa: String,
+        formato: FormatoGrafo,
+    },
+    Svg {
+        mapa: String,
+        posiciones: Vec<(usize, usize)>,
+        salida: String,
+    },
+    Ayuda,
+}
+
+/// Formato en el que se escribe un mapa.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FormatoMapa {
+    Texto,
+    Json,
+}
+
+/// Cuando se dibuja el mapa con colores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModoColor {
+    /// Solo si la salida estandar es una terminal y NO_COLOR no esta definida.
+    Auto,
+    Siempre,
+    Nunca,
+}
+
+/// Formato en el que se exporta el grafo de reacciones en cadena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FormatoGrafo {
+    Dot,
+    Json,
+}
+
+/// Error al ejecutar un comando, junto con el codigo de salida que le corresponde.
+struct ErrorComando {
+    codigo: i32,
+    mensaje: String,
+}
+
+impl ErrorComando {
+    fn ejecucion(mensaje: String) -> Self {
+        ErrorComando {
+            codigo: EXIT_ERROR,
+            mensaje,
+        }
+    }
+}
+
+impl From<ParseError> for ErrorComando {
+    fn from(error: ParseError) -> Self {
+        ErrorComando {
+            codigo: EXIT_MAPA_INVALIDO,
+            mensaje: format!("Mapa invalido: {}", error),
+        }
+    }
+}
+
+/// Devuelve el unico argumento de un comando que solo recibe el mapa.
+fn argumento_mapa(nombre: &str, argumentos: &[String]) -> Result<String, String> {
+    match argumentos {
+        [mapa] => Ok(mapa.clone()),
+        _ => Err(format!("{} recibe unicamente el mapa", nombre)),
+    }
+}
+
+/// Parsea el formato en el que se escribe un mapa: texto o json.
+fn parsear_formato_mapa(formato: &str) -> Result<FormatoMapa, String> {
+    match formato {
+        "texto" => Ok(FormatoMapa::Texto),
+        "json" => Ok(FormatoMapa::Json),
+        _ => Err(format!("Formato de mapa desconocido: {}", formato)),
+    }
+}
+
+/// Parsea los argumentos del comando play.
+fn parsear_jugar(argumentos: &[String]) -> Result<Comando, String> {
+    let mut mapa = None;
+    let mut posiciones = Vec::new();
+    let mut salida = ESTANDAR.to_string();
+    let mut formato = FormatoMapa::Texto;
+    let mut argumentos = argumentos.iter();
+    while let Some(argumento) = argumentos.next() {
+        match argumento.as_str() {
+            FLAG_DETONAR => match argumentos.next() {
+                Some(posicion) => posiciones.push(parsear_posicion(posicion)?),
+                None => return Err(format!("Falta la posicion despues de {}", FLAG_DETONAR)),
+            },
+            "--salida" => match argumentos.next() {
+                Some(archivo) => salida = archivo.clone(),
+                None => return Err("Falta el archivo despues de --salida".to_string()),
+            },
+            "--formato" => match argumentos.next() {
+                Some(valor) => formato = parsear_formato_mapa(valor)?,
+                None => return Err("Falta el formato despues de --formato".to_string()),
+            },
+            _ if mapa.is_none() => mapa = Some(argumento.clone()),
+            _ => return Err(format!("Argumento inesperado: {}", argumento)),
+        }
+    }
+    match mapa {
+        None => Err("play necesita un mapa".to_string()),
+        Some(_) if posiciones.is_empty() => Err(format!(
+            "play necesita al menos una posicion con {}",
+            FLAG_DETONAR
+        )),
+        Some(mapa) => Ok(Comando::Jugar {
+            mapa,
+            posiciones,
+            salida,
+            formato,
+        }),
+    }
+}
+
+/// Parsea los argumentos del comando validate.
+fn parsear_validar(argumentos: &[String]) -> Result<Comando, String> {
+    match argumentos {
+        [mapa] => Ok(Comando::Validar {
+            mapa: mapa.clone(),
+            estricto: false,
+        }),
+        [mapa, estricto] | [estricto, mapa] if estricto == "--estricto" => Ok(Comando::Validar {
+            mapa: mapa.clone(),
+            estricto: true,
+        }),
+        _ => Err("validate recibe el mapa y opcionalmente --estricto".to_string()),
+    }
+}
+
+/// Parsea cuando se usan colores: auto, always o never.
+fn parsear_color(color: &str) -> Result<ModoColor, String> {
+    match color {
+        "auto" => Ok(ModoColor::Auto),
+        "always" => Ok(ModoColor::Siempre),
+        "never" => Ok(ModoColor::Nunca),
+        _ => Err(format!("Modo de color desconocido: {}", color)),
+    }
+}
+
+/// Parsea los argumentos del comando render.
+fn parsear_renderizar(argumentos: &[String]) -> Result<Comando, String> {
+    let mut mapa = None;
+    let mut formato = FormatoMapa::Texto;
+    let mut color = ModoColor::Auto;
+    let mut posiciones = Vec::new();
+    let mut argumentos = argumentos.iter();
+    while let Some(argumento) = argumentos.next() {
+        match argumento.as_str() {
+            "--formato" => match argumentos.next() {
+                Some(valor) => formato = parsear_formato_mapa(valor)?,
+                None => return Err("Falta el formato despues de --formato".to_string()),
+            },
+            "--color" => match argumentos.next() {
+                Some(valor) => color = parsear_color(valor)?,
+                None => return Err("Falta el modo despues de --color".to_string()),
+            },
+            FLAG_DETONAR => match argumentos.next() {
+                Some(posicion) => posiciones.push(parsear_posicion(posicion)?),
+                None => return Err(format!("Falta la posicion despues de {}", FLAG_DETONAR)),
+            },
+            _ if mapa.is_none() => mapa = Some(argumento.clone()),
+            _ => return Err(format!("Argumento inesperado: {}", argumento)),
+        }
+    }
+    match mapa {
+        None => Err("render necesita un mapa".to_string()),
+        Some(mapa) => Ok(Comando::Renderizar {
+            mapa,
+            formato,
+            color,
+            posiciones,
+        }),
+    }
+}
+
+/// Parsea los argumentos del comando heatmap.
+fn parsear_cobertura(argumentos: &[String]) -> Result<Comando, String> {
+    match argumentos {
+        [mapa] => Ok(Comando::Cobertura {
+            mapa: mapa.clone(),
+            ansi: false,
+        }),
+        [mapa, ansi] | [ansi, mapa] if ansi == "--ansi" => Ok(Comando::Cobertura {
+            mapa: mapa.clone(),
+            ansi: true,
+        }),
+        _ => Err("heatmap recibe el mapa y opcionalmente --ansi".to_string()),
+    }
+}
+
+/// Parsea los argumentos del comando graph.
+fn parsear_grafo(argumentos: &[String]) -> Result<Comando, String> {
+    let (mapa, formato) = match argumentos {
+        [mapa] => (mapa, "dot"),
+        [mapa, flag, formato] | [flag, formato, mapa] if flag == "--formato" => {
+            (mapa, formato.as_str())
+        }
+        _ => return Err("graph recibe el mapa y opcionalmente --formato dot|json".to_string()),
+    };
+    let formato = match formato {
+        "dot" => FormatoGrafo::Dot,
+        "json" => FormatoGrafo::Json,
+        _ => return Err(format!("Formato de grafo desconocido: {}", formato)),
+    };
+    Ok(Comando::Grafo {
+        mapa: mapa.clone(),
+        formato,
+    })
+}
+
+/// Parsea los argumentos del comando svg.
+fn parsear_svg(argumentos: &[String]) -> Result<Comando, String> {
+    let mut mapa = None;
+    let mut posiciones = Vec::new();
+    let mut salida = ESTANDAR.to_string();
+    let mut argumentos = argumentos.iter();
+    while let Some(argumento) = argumentos.next() {
+        match argumento.as_str() {
+            FLAG_DETONAR => match argumentos.next() {
+                Some(posicion) => posiciones.push(parsear_posicion(posicion)?),
+                None => return Err(format!("Falta la posicion despues de {}", FLAG_DETONAR)),
+            },
+            "--salida" => match argumentos.next() {
+                Some(archivo) => salida = archivo.clone(),
+                None => return Err("Falta el archivo despues de --salida".to_string()),
+            },
+            _ if mapa.is_none() => mapa = Some(argumento.clone()),
+            _ => return Err(format!("Argumento inesperado: {}", argumento)),
+        }
+    }
+    match mapa {
+        None => Err("svg necesita un mapa".to_string()),
+        Some(mapa) => Ok(Comando::Svg {
+            mapa,
+            posiciones,
+            salida,
+        }),
+    }
+}
+
+/// Parsea los argumentos de linea de comando, sin incluir el nombre del programa.
+pub fn parsear_argumentos(argumentos: &[String]) -> Result<Comando, String> {
+    let (comando, resto) = match argumentos.split_first() {
+        Some((comando, resto)) => (comando.as_str(), resto),
+        None => return Err("Falta el comando".to_string()),
+    };
+    match comando {
+        "play" => parsear_jugar(resto),
+        "validate" => parsear_validar(resto),
+        "render" => parsear_renderizar(resto),
+        "solve" => Ok(Comando::Resolver {
+            mapa: argumento_mapa(comando, resto)?,
+        }),
+        "stats" => Ok(Comando::Estadisticas {
+            mapa: argumento_mapa(comando, resto)?,
+        }),
+        "heatmap" => parsear_cobertura(resto),
+        "graph" => parsear_grafo(resto),
+        "svg" => parsear_svg(resto),
+        "help" | "--help" | "-h" => Ok(Comando::Ayuda),
+        _ => Err(format!("Comando desconocido: {}", comando)),
+    }
+}
+
+/// Lee el mapa del archivo indicado, o de la entrada estandar si el archivo es -.
+/// Si el contenido es JSON lo transforma como tal, si no lo transforma como un mapa en formato texto.
+fn leer_mapa(origen: &str) -> Result<Mapa, ParseError> {
+    if origen != ESTANDAR {
+        return bomberman_game::cargar_mapa(origen);
+    }
+    let mut texto = String::new();
+    if let Err(why) = std::io::stdin().read_to_string(&mut texto) {
+        return Err(ParseError::Archivo {
+            path: origen.to_string(),
+            mensaje: why.to_string(),
+        });
+    }
+    bomberman_game::mapa_desde_texto(&texto)
+}
+
+/// Devuelve el mapa escrito en el formato pedido.
+fn mapa_en_formato(mapa: &Mapa, formato: FormatoMapa) -> String {
+    match formato {
+        FormatoMapa::Texto => mapa.to_string(),
+        FormatoMapa::Json => json::mapa_a_json(mapa),
+    }
+}
+
+/// Devuelve si hay que usar colores al escribir en la salida estandar.
+pub fn usar_color(color: ModoColor) -> bool {
+    match color {
+        ModoColor::Siempre => true,
+        ModoColor::Nunca => false,
+        ModoColor::Auto => {
+            std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+        }
+    }
+}
+
+/// Muestra el mapa despues de detonar las posiciones indicadas.
+/// Con colores dibuja el tablero resaltando lo que alcanzo la ultima detonacion; sin colores lo escribe en el formato pedido.
+fn renderizar(
+    mapa: &str,
+    formato: FormatoMapa,
+    color: ModoColor,
+    posiciones: &[(usize, usize)],
+) -> Result<String, ErrorComando> {
+    let mut mapa = leer_mapa(mapa)?;
+    let eventos = detonar_en_orden(&mut mapa, posiciones).map_err(ErrorComando::ejecucion)?;
+    if formato == FormatoMapa::Texto && usar_color(color) {
+        let resaltadas = dibujo::casillas_alcanzadas(&eventos);
+        return Ok(dibujo::dibujar(&mapa, &resaltadas));
+    }
+    Ok(mapa_en_formato(&mapa, formato))
+}
+
+/// Exporta el mapa como SVG. Si hay posiciones, las detona en orden y dibuja el mapa
+/// tal como estaba antes de la ultima detonacion, con el recorrido de esa explosion encima.
+fn exportar_svg(mapa: &str, posiciones: &[(usize, usize)]) -> Result<String, ErrorComando> {
+    let mut mapa = leer_mapa(mapa)?;
+    let (ultima, anteriores) = match posiciones.split_last() {
+        Some((ultima, anteriores)) => (ultima, anteriores),
+        None => return Ok(svg::mapa_a_svg(&mapa, &[])),
+    };
+    detonar_en_orden(&mut mapa, anteriores).map_err(ErrorComando::ejecucion)?;
+    let antes = mapa.clone();
+    let eventos =
+        detonar_posicion(&mut mapa, *ultima, posiciones.len()).map_err(ErrorComando::ejecucion)?;
+    Ok(svg::mapa_a_svg(&antes, &eventos))
+}
+
+/// Escribe el texto en el archivo indicado, o en salida si el archivo es -.
+fn escribir(destino: &str, texto: &str, salida: &mut dyn Write) -> Result<(), ErrorComando> {
+    let resultado = if destino == ESTANDAR {
+        salida.write_all(texto.as_bytes())
+    } else {
+        File::create(destino).and_then(|mut archivo| archivo.write_all(texto.as_bytes()))
+    };
+    resultado.map_err(|why| {
+        ErrorComando::ejecucion(format!("No se pudo escribir en {}: {}", destino, why))
+    })
+}
+
+/// Devuelve la cantidad de tiles de cada tipo que hay en el mapa.
+fn estadisticas(mapa: &Mapa) -> String {
+    let (mut enemigos, mut vida_total, mut bombas, mut especiales) = (0, 0, 0, 0);
+    let (mut desvios, mut piedras, mut paredes, mut vacios) = (0, 0, 0, 0);
+    let (mut jugadores, mut premios) = (0, 0);
+    for tile in mapa.tiles.iter().flatten() {
+        match tile {
+            Tile::Enemigo(enemigo) => {
+                enemigos += 1;
+                vida_total += enemigo.vida as u64;
+            }
+            Tile::BombaNormal(_) => bombas += 1,
+            Tile::BombaEspecial(_) => especiales += 1,
+            Tile::Desvio(_) => desvios += 1,
+            Tile::Piedra(_) => piedras += 1,
+            Tile::Pared(_) => paredes += 1,
+            Tile::Jugador(_) => jugadores += 1,
+            Tile::Premio(_) => premios += 1,
+            Tile::Vacio => vacios += 1,
+        }
+    }
+    format!(
+        "ancho: {}\nalto: {}\nenemigos: {}\nvida total de enemigos: {}\nbombas normales: {}\nbombas especiales: {}\ndesvios: {}\npiedras: {}\nparedes: {}\njugadores: {}\npremios: {}\nvacios: {}\n",
+        mapa.ancho, mapa.alto, enemigos, vida_total, bombas, especiales, desvios, piedras, paredes, jugadores, premios, vacios
+    )
+}
+
+/// Busca la solucion del mapa y la devuelve con el formato de los argumentos de play.
+fn resolver(mapa: &Mapa) -> Result<String, ErrorComando> {
+    match solver::resolver(mapa) {
+        Some(detonaciones) => {
+            let argumentos: Vec<String> = detonaciones
+                .iter()
+                .map(|bomba| format!("{} {},{}", FLAG_DETONAR, bomba.x, bomba.y))
+                .collect();
+            Ok(format!(
+                "detonaciones necesarias: {}\n{}\n",
+                detonaciones.len(),
+                argumentos.join(" ")
+            ))
+        }
+        None => Err(ErrorComando::ejecucion(
+            "No es posible eliminar a todos los enemigos".to_string(),
+        )),
+    }
+}
+
+/// Valida el mapa y escribe los hallazgos, uno por linea.
+/// Falla con EXIT_MAPA_INVALIDO si hay errores, o si hay advertencias y estricto=true.
+fn validar(origen: &str, estricto: bool, salida: &mut dyn Write) -> Result<(), ErrorComando> {
+    let mapa = leer_mapa(origen)?;
+    let hallazgos = validador::validar(&mapa);
+    let errores = hallazgos
+        .iter()
+        .filter(|hallazgo| hallazgo.severidad == Severidad::Error)
+        .count();
+    let advertencias = hallazgos.len() - errores;
+
+    let mut texto = format!("{}: mapa de {}x{}\n", origen, mapa.ancho, mapa.alto);
+    for hallazgo in hallazgos.iter() {
+        texto.push_str(&format!("{}\n", hallazgo));
+    }
+    texto.push_str(&format!(
+        "{} errores, {} advertencias\n",
+        errores, advertencias
+    ));
+    escribir(ESTANDAR, &texto, salida)?;
+
+    if errores > 0 || (estricto && advertencias > 0) {
+        return Err(ErrorComando {
+            codigo: EXIT_MAPA_INVALIDO,
+            mensaje: format!("{}: el mapa no paso la validacion", origen),
+        });
+    }
+    Ok(())
+}
+
+/// Ejecuta el comando escribiendo en salida lo que va a la salida estandar, y devuelve el error que corresponda si fallo.
+fn ejecutar_comando(comando: Comando, salida: &mut dyn Write) -> Result<(), ErrorComando> {
+    match comando {
+        Comando::Jugar {
+            mapa,
+            posiciones,
+            salida: destino,
+            formato,
+        } => {
+            let mut mapa = leer_mapa(&mapa)?;
+            detonar_en_orden(&mut mapa, &posiciones).map_err(ErrorComando::ejecucion)?;
+            escribir(&destino, &mapa_en_formato(&mapa, formato), salida)
+        }
+        Comando::Validar { mapa, estricto } => validar(&mapa, estricto, salida),
+        Comando::Renderizar {
+            mapa,
+            formato,
+            color,
+            posiciones,
+        } => escribir(
+            ESTANDAR,
+            &renderizar(&mapa, formato, color, &posiciones)?,
+            salida,
+        ),
+        Comando::Resolver { mapa } => escribir(ESTANDAR, &resolver(&leer_mapa(&mapa)?)?, salida),
+        Comando::Estadisticas { mapa } => {
+            escribir(ESTANDAR, &estadisticas(&leer_mapa(&mapa)?), salida)
+        }
+        Comando::Cobertura { mapa, ansi } => {
+            let mapa = leer_mapa(&mapa)?;
+            let cobertura = cobertura::calcular_cobertura(&mapa);
+            escribir(
+                ESTANDAR,
+                &cobertura::cobertura_a_string(&mapa, &cobertura, ansi),
+                salida,
+            )
+        }
+        Comando::Grafo { mapa, formato } => {
+            let grafo = grafo::construir_grafo(&leer_mapa(&mapa)?);
+            let texto = match formato {
+                FormatoGrafo::Dot => grafo.a_dot(),
+                FormatoGrafo::Json => grafo.a_json(),
+            };
+            escribir(ESTANDAR, &texto, salida)
+        }
+        Comando::Svg {
+            mapa,
+            posiciones,
+            salida: destino,
+        } => escribir(&destino, &exportar_svg(&mapa, &posiciones)?, salida),
+        Comando::Ayuda => escribir(ESTANDAR, &format!("{}\n", AYUDA), salida),
+    }
+}
+
+/// Ejecuta la forma original del programa: maze.txt output_dir seguido de las posiciones a detonar.
+/// Los errores al jugar se escriben en el archivo de salida y se avisa por la salida de errores.
+fn ejecutar_forma_original(argumentos: &[String], salida: &mut dyn Write) -> i32 {
+    let mut output_file = match bomberman_game::inicializar_output_dir(argumentos) {
+        Err(why) => {
+            eprintln!("No se pudo abrir el directorio de salida: {why}");
+            return EXIT_ERROR;
+        }
+        Ok(output_dir) => output_dir,
+    };
+
+    match bomberman_game::jugar(argumentos) {
+        Err(why) => {
+            eprintln!("Error al ejecutar, dirijase al archivo de output");
+            let _ = print_err_to_file(format!("No se pudo jugar: {why}"), output_file);
+            EXIT_ERROR
+        }
+        Ok(mapa) => {
+            if let Err(why) = print_mapa_to_file(&mapa, &mut output_file) {
+                eprintln!("No se pudo escribir el archivo de output: {why}");
+                return EXIT_ERROR;
+            }
+            let _ = writeln!(
+                salida,
+                "Juego terminado satisfactoriamente, dirijase al archivo de output"
+            );
+            EXIT_OK
+        }
+    }
+}
+
+/// Ejecuta el programa con los argumentos de linea de comando, incluyendo el nombre del programa.
+/// Lo que va a la salida estandar se escribe en salida; los errores van siempre a la salida de errores.
+/// Devuelve el codigo de salida del proceso.
+pub fn ejecutar(argumentos: &[String], salida: &mut dyn Write) -> i32 {
+    let resto = argumentos.get(1..).unwrap_or(&[]);
+    if resto.len() >= 4 && !COMANDOS.contains(&resto[0].as_str()) {
+        return ejecutar_forma_original(argumentos, salida);
+    }
+    let comando = match parsear_argumentos(resto) {
+        Ok(comando) => comando,
+        Err(why) => {
+            eprintln!("{}\n\n{}", why, AYUDA);
+            return EXIT_USO_INCORRECTO;
+        }
+    };
+    match ejecutar_comando(comando, salida) {
+        Ok(()) => EXIT_OK,
+        Err(error) => {
+            eprintln!("{}", error.mensaje);
+            error.codigo
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn argumentos(valores: &[&str]) -> Vec<String> {
+        valores.iter().map(|valor| valor.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parsear_jugar() {
+        let comando = parsear_argumentos(&argumentos(&[
+            "play",
+            "mapa.txt",
+            "--detonar",
+            "0,0",
+            "--salida",
+            "out.txt",
+            "--detonar",
+            "4,2",
+        ]));
+        assert_eq!(
+            comando,
+            Ok(Comando::Jugar {
+                mapa: "mapa.txt".to_string(),
+                posiciones: vec![(0, 0), (4, 2)],
+                salida: "out.txt".to_string(),
+                formato: FormatoMapa::Texto,
+            })
+        );
+
+        let comando = parsear_argumentos(&argumentos(&[
+            "play",
+            "-",
+            "--detonar",
+            "1,1",
+            "--formato",
+            "json",
+        ]));
+        assert_eq!(
+            comando,
+            Ok(Comando::Jugar {
+                mapa: "-".to_string(),
+                posiciones: vec![(1, 1)],
+                salida: "-".to_string(),
+                formato: FormatoMapa::Json,
+            })
+        );
+
+        assert!(parsear_argumentos(&argumentos(&[
+            "play",
+            "mapa.txt",
+            "--detonar",
+            "1,1",
+            "--formato",
+            "xml"
+        ]))
+        .is_err());
+
+        assert!(parsear_argumentos(&argumentos(&["play", "mapa.txt"])).is_err());
+        assert!(parsear_argumentos(&argumentos(&["play", "a.txt", "b.txt"])).is_err());
+    }
+
+    #[test]
+    fn test_parsear_otros_comandos() {
+        assert_eq!(
+            parsear_argumentos(&argumentos(&["validate", "mapa.txt"])),
+            Ok(Comando::Validar {
+                mapa: "mapa.txt".to_string(),
+                estricto: false
+            })
+        );
+        assert_eq!(
+            parsear_argumentos(&argumentos(&["validate", "--estricto", "mapa.txt"])),
+            Ok(Comando::Validar {
+                mapa: "mapa.txt".to_string(),
+                estricto: true
+            })
+        );
+        assert_eq!(
+            parsear_argumentos(&argumentos(&["render", "--formato", "json", "mapa.txt"])),
+            Ok(Comando::Renderizar {
+                mapa: "mapa.txt".to_string(),
+                formato: FormatoMapa::Json,
+                color: ModoColor::Auto,
+                posiciones: vec![],
+            })
+        );
+        assert_eq!(
+            parsear_argumentos(&argumentos(&[
+                "render",
+                "mapa.txt",
+                "--color",
+                "always",
+                "--detonar",
+                "0,4",
+            ])),
+            Ok(Comando::Renderizar {
+                mapa: "mapa.txt".to_string(),
+                formato: FormatoMapa::Texto,
+                color: ModoColor::Siempre,
+                posiciones: vec![(0, 4)],
+            })
+        );
+        assert!(
+            parsear_argumentos(&argumentos(&["render", "mapa.txt", "--color", "rojo"])).is_err()
+        );
+        assert_eq!(
+            parsear_argumentos(&argumentos(&["--help"])),
+            Ok(Comando::Ayuda)
+        );
+        assert_eq!(
+            parsear_argumentos(&argumentos(&["heatmap", "--ansi", "mapa.txt"])),
+            Ok(Comando::Cobertura {
+                mapa: "mapa.txt".to_string(),
+                ansi: true
+            })
+        );
+        assert_eq!(
+            parsear_argumentos(&argumentos(&["graph", "mapa.txt", "--formato", "json"])),
+            Ok(Comando::Grafo {
+                mapa: "mapa.txt".to_string(),
+                formato: FormatoGrafo::Json
+            })
+        );
+        assert!(
+            parsear_argumentos(&argumentos(&["graph", "mapa.txt", "--formato", "png"])).is_err()
+        );
+        assert_eq!(
+            parsear_argumentos(&argumentos(&["svg", "mapa.txt", "--detonar", "1,2"])),
+            Ok(Comando::Svg {
+                mapa: "mapa.txt".to_string(),
+                posiciones: vec![(1, 2)],
+                salida: "-".to_string(),
+            })
+        );
+        assert!(parsear_argumentos(&argumentos(&["stats"])).is_err());
+        assert!(parsear_argumentos(&argumentos(&["volar", "mapa.txt"])).is_err());
+        assert!(parsear_argumentos(&[]).is_err());
+    }
+
+    /// Ejecuta el programa y devuelve el codigo de salida junto con lo que escribio en la salida estandar.
+    fn correr(valores: &[&str]) -> (i32, String) {
+        let mut salida = Vec::new();
+        let codigo = ejecutar(&argumentos(valores), &mut salida);
+        (codigo, String::from_utf8(salida).unwrap())
+    }
+
+    #[test]
+    fn test_codigos_de_salida() {
+        let (codigo, salida) = correr(&["bin", "validate", "mapas/mapa_1.txt"]);
+        assert_eq!(codigo, EXIT_OK);
+        assert!(
+            salida.ends_with("0 errores, 0 advertencias\n"),
+            "{}",
+            salida
+        );
+
+        let (codigo, _) = correr(&["bin", "validate", "mapas/mapa_test_token_invalido.txt"]);
+        assert_eq!(codigo, EXIT_MAPA_INVALIDO);
+
+        let (codigo, _) = correr(&["bin", "play", "mapas/mapa_1.txt", "--detonar", "1,1"]);
+        assert_eq!(codigo, EXIT_ERROR);
+
+        let (codigo, _) = correr(&["bin", "render", "mapas/mapa_test_json.json"]);
+        assert_eq!(codigo, EXIT_OK);
+
+        let (codigo, _) = correr(&["bin", "validate", "mapas/mapa_test_json.json"]);
+        assert_eq!(codigo, EXIT_MAPA_INVALIDO);
+
+        let (codigo, _) = correr(&["bin", "validate", "mapas/mapa_4.txt"]);
+        assert_eq!(codigo, EXIT_MAPA_INVALIDO);
+
+        let (codigo, _) = correr(&["bin", "validate", "--estricto", "mapas/mapa_5.txt"]);
+        assert_eq!(codigo, EXIT_MAPA_INVALIDO);
+
+        let (codigo, _) = correr(&["bin", "validate", "mapas/mapa_5.txt"]);
+        assert_eq!(codigo, EXIT_OK);
+
+        let (codigo, salida) = correr(&["bin", "solve", "mapas/mapa_3.txt"]);
+        assert_eq!(codigo, EXIT_OK);
+        assert!(
+            salida.starts_with("detonaciones necesarias: "),
+            "{}",
+            salida
+        );
+
+        let (codigo, _) = correr(&[
+            "bin",
+            "graph",
+            "--formato",
+            "json",
+            "mapas/mapa_test_token_invalido.txt",
+        ]);
+        assert_eq!(codigo, EXIT_MAPA_INVALIDO);
+
+        let (codigo, _) = correr(&[
+            "bin",
+            "svg",
+            "mapas/mapa_3.txt",
+            "--detonar",
+            "0,4",
+            "--detonar",
+            "0,4",
+        ]);
+        assert_eq!(codigo, EXIT_ERROR);
+
+        let (codigo, salida) = correr(&["bin", "volar"]);
+        assert_eq!(codigo, EXIT_USO_INCORRECTO);
+        assert!(salida.is_empty());
+    }
+
+    #[test]
+    fn test_svg_informa_la_detonacion_que_fallo() {
+        let error = exportar_svg("mapas/mapa_3.txt", &[(0, 4), (0, 4)])
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.mensaje,
+            "No hay bomba en la posicion (0, 4) al momento de la detonacion 2"
+        );
+    }
+}
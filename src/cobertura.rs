@@ -0,0 +1,124 @@
+use crate::modelo::{evento::Evento, mapa::Mapa, tile::Tile};
+use crate::turno;
+use std::collections::HashSet;
+
+/// Colores de fondo ANSI usados para el heatmap, de menor a mayor cobertura.
+/// La ultima posicion se usa para cualquier cobertura mayor.
+const COLORES_ANSI: [&str; 5] = ["\x1b[41m", "\x1b[44m", "\x1b[42m", "\x1b[43m", "\x1b[45m"];
+const RESET_ANSI: &str = "\x1b[0m";
+
+/// Calcula cuantas bombas alcanzan cada casilla del mapa si se las detona de a una, empezando desde el mapa original.
+/// Cada detonacion incluye los desvios y las reacciones en cadena que provoca.
+/// Una casilla suma a lo sumo uno por bomba, aunque la explosion la alcance mas de una vez.
+/// El resultado se indexa igual que mapa.tiles: cobertura[y][x].
+pub fn calcular_cobertura(mapa: &Mapa) -> Vec<Vec<u32>> {
+    let mut cobertura = vec![vec![0; mapa.ancho]; mapa.alto];
+    for (y_pos, fila) in mapa.tiles.iter().enumerate() {
+        for (x_pos, tile) in fila.iter().enumerate() {
+            if !matches!(tile, Tile::BombaNormal(_) | Tile::BombaEspecial(_)) {
+                continue;
+            }
+            let mut simulado = mapa.clone();
+            let eventos = match turno::jugar_turno_con_eventos(&mut simulado, x_pos, y_pos) {
+                Ok(eventos) => eventos,
+                Err(_) => continue,
+            };
+            let alcanzadas: HashSet<_> = eventos
+                .iter()
+                .filter_map(|evento| match evento {
+                    Evento::TileAlcanzado { tile, .. } => Some(*tile),
+                    _ => None,
+                })
+                .collect();
+            for coordenada in alcanzadas {
+                cobertura[coordenada.y][coordenada.x] += 1;
+            }
+        }
+    }
+    cobertura
+}
+
+/// Devuelve el heatmap como texto, una fila por linea.
+/// Las paredes se muestran como W y las casillas que ninguna bomba alcanza como un punto.
+/// Si ansi=true, cada casilla tiene ademas un color de fondo segun su cobertura.
+pub fn cobertura_a_string(mapa: &Mapa, cobertura: &[Vec<u32>], ansi: bool) -> String {
+    let ancho_celda = cobertura
+        .iter()
+        .flatten()
+        .map(|valor| valor.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let mut string = String::new();
+    for (fila_tiles, fila_cobertura) in mapa.tiles.iter().zip(cobertura) {
+        let celdas: Vec<String> = fila_tiles
+            .iter()
+            .zip(fila_cobertura)
+            .map(|(tile, valor)| {
+                let texto = match (tile, valor) {
+                    (Tile::Pared(_), _) => "W".to_string(),
+                    (_, 0) => ".".to_string(),
+                    (_, valor) => valor.to_string(),
+                };
+                let texto = format!("{:>ancho$}", texto, ancho = ancho_celda);
+                if ansi && !matches!(tile, Tile::Pared(_)) {
+                    let color = COLORES_ANSI[(*valor as usize).min(COLORES_ANSI.len() - 1)];
+                    format!("{}{}{}", color, texto, RESET_ANSI)
+                } else {
+                    texto
+                }
+            })
+            .collect();
+        string.push_str(&celdas.join(" "));
+        string.push('\n');
+    }
+    string
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bomberman_game::transformar_a_mapa;
+    use crate::modelo::{bomba::Bomba, enemigo::Enemigo, obstaculo::Obstaculo};
+
+    #[test]
+    fn test_cobertura_con_reaccion_en_cadena() {
+        let mapa = Mapa {
+            ancho: 4,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 1, false)),
+                Tile::BombaNormal(Bomba::crear(1, 0, 1, false)),
+                Tile::Enemigo(Enemigo::crear(2, 0, 1)),
+                Tile::Vacio,
+            ]],
+        };
+        assert_eq!(calcular_cobertura(&mapa), vec![vec![2, 2, 2, 0]]);
+    }
+
+    #[test]
+    fn test_cobertura_a_string() {
+        let mapa = Mapa {
+            ancho: 3,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 3, false)),
+                Tile::Vacio,
+                Tile::Pared(Obstaculo::crear(2, 0, true)),
+            ]],
+        };
+        let cobertura = calcular_cobertura(&mapa);
+        assert_eq!(cobertura_a_string(&mapa, &cobertura, false), ". 1 W\n");
+        assert_eq!(
+            cobertura_a_string(&mapa, &cobertura, true),
+            "\x1b[41m.\x1b[0m \x1b[44m1\x1b[0m W\n"
+        );
+    }
+
+    #[test]
+    fn test_cobertura_mapa_3() {
+        let mapa = transformar_a_mapa("mapas/mapa_3.txt").unwrap();
+        let cobertura = calcular_cobertura(&mapa);
+        assert_eq!(cobertura[2][4], 3);
+        assert_eq!(cobertura[6][6], 0);
+    }
+}
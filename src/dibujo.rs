@@ -0,0 +1,171 @@
+use crate::modelo::{
+    constantes::ENEMIGO, coordenada::Coordenada, direccion::Direccion, evento::Evento, mapa::Mapa,
+    tile::Tile,
+};
+use std::collections::HashSet;
+
+const RESET_ANSI: &str = "\x1b[0m";
+/// Invierte el color de fondo y el de la letra para resaltar una casilla sin perder su color.
+const RESALTADO_ANSI: &str = "\x1b[7m";
+const PARED_ANSI: &str = "\x1b[90m";
+const PIEDRA_ANSI: &str = "\x1b[33m";
+const BOMBA_ANSI: &str = "\x1b[1;34m";
+const BOMBA_ESPECIAL_ANSI: &str = "\x1b[1;35m";
+const DESVIO_ANSI: &str = "\x1b[36m";
+const VACIO_ANSI: &str = "\x1b[2m";
+const JUGADOR_ANSI: &str = "\x1b[1;97m";
+const PREMIO_ANSI: &str = "\x1b[1;96m";
+/// Colores de los enemigos segun su vida: rojo con 1, amarillo con 2 y verde con 3 o mas.
+const ENEMIGO_ANSI: [&str; 3] = ["\x1b[1;31m", "\x1b[1;93m", "\x1b[1;32m"];
+
+/// Devuelve la flecha que representa la direccion de un desvio.
+fn flecha(direccion: Direccion) -> char {
+    match direccion {
+        Direccion::Arriba => '↑',
+        Direccion::Abajo => '↓',
+        Direccion::Izquierda => '←',
+        Direccion::Derecha => '→',
+    }
+}
+
+/// Devuelve el texto y el color con el que se dibuja un tile.
+/// Los desvios se dibujan como flechas y las casillas vacias como un punto; el resto usa su token del mapa.
+/// A los enemigos no se les dibujan las bombas que ya los impactaron, para no ensanchar todas las casillas.
+fn simbolo(tile: &Tile) -> (String, &'static str) {
+    match tile {
+        Tile::Enemigo(enemigo) => {
+            let indice = (enemigo.vida as usize).clamp(1, ENEMIGO_ANSI.len()) - 1;
+            let mut texto = format!("{}{}", ENEMIGO, enemigo.vida);
+            texto.extend(enemigo.movimiento.char_movimiento());
+            (texto, ENEMIGO_ANSI[indice])
+        }
+        Tile::BombaNormal(_) => (tile.to_string(), BOMBA_ANSI),
+        Tile::BombaEspecial(_) => (tile.to_string(), BOMBA_ESPECIAL_ANSI),
+        Tile::Piedra(_) => (tile.to_string(), PIEDRA_ANSI),
+        Tile::Pared(_) => (tile.to_string(), PARED_ANSI),
+        Tile::Desvio(desvio) => (flecha(desvio.direccion).to_string(), DESVIO_ANSI),
+        Tile::Jugador(_) => (tile.to_string(), JUGADOR_ANSI),
+        Tile::Premio(_) => (tile.to_string(), PREMIO_ANSI),
+        Tile::Vacio => (".".to_string(), VACIO_ANSI),
+    }
+}
+
+/// Devuelve las casillas afectadas por una detonacion a partir de sus eventos:
+/// las bombas que explotaron, todas las casillas que alcanzaron sus explosiones y las piedras que destruyeron.
+pub fn casillas_alcanzadas(eventos: &[Evento]) -> HashSet<Coordenada> {
+    eventos
+        .iter()
+        .filter_map(|evento| match evento {
+            Evento::BombaDetonada { bomba, .. } => Some(*bomba),
+            Evento::TileAlcanzado { tile, .. } => Some(*tile),
+            Evento::PiedraDestruida { piedra, .. } => Some(*piedra),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Dibuja el mapa con colores ANSI, una fila por linea.
+/// Todas las casillas tienen el mismo ancho para que las columnas queden alineadas.
+/// Las casillas en resaltadas se muestran con los colores invertidos.
+pub fn dibujar(mapa: &Mapa, resaltadas: &HashSet<Coordenada>) -> String {
+    let simbolos: Vec<Vec<(String, &str)>> = mapa
+        .tiles
+        .iter()
+        .map(|fila| fila.iter().map(simbolo).collect())
+        .collect();
+    let ancho_celda = simbolos
+        .iter()
+        .flatten()
+        .map(|(texto, _)| texto.chars().count())
+        .max()
+        .unwrap_or(1);
+
+    let mut dibujo = String::new();
+    for (y, fila) in simbolos.iter().enumerate() {
+        let celdas: Vec<String> = fila
+            .iter()
+            .enumerate()
+            .map(|(x, (texto, color))| {
+                let resaltado = if resaltadas.contains(&Coordenada { x, y }) {
+                    RESALTADO_ANSI
+                } else {
+                    ""
+                };
+                format!(
+                    "{}{}{:<ancho$}{}",
+                    color,
+                    resaltado,
+                    texto,
+                    RESET_ANSI,
+                    ancho = ancho_celda
+                )
+            })
+            .collect();
+        dibujo.push_str(&celdas.join(" "));
+        dibujo.push('\n');
+    }
+    dibujo
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::turno;
+
+    /// Saca las secuencias ANSI de un texto, dejando solo lo que se ve.
+    fn sin_colores(texto: &str) -> String {
+        let mut limpio = String::new();
+        let mut en_escape = false;
+        for c in texto.chars() {
+            match c {
+                '\x1b' => en_escape = true,
+                'm' if en_escape => en_escape = false,
+                _ if en_escape => {}
+                c => limpio.push(c),
+            }
+        }
+        limpio
+    }
+
+    #[test]
+    fn test_dibujar_alinea_y_usa_flechas() {
+        let mapa: Mapa = "F12 B1 DU\nDL _ S2\nDR W DD".parse().unwrap();
+        let dibujo = dibujar(&mapa, &HashSet::new());
+        assert_eq!(
+            sin_colores(&dibujo),
+            "F12 B1  ↑  \n←   .   S2 \n→   W   ↓  \n"
+        );
+        assert!(dibujo.starts_with("\x1b[1;32mF12\x1b[0m \x1b[1;34mB1 \x1b[0m"));
+        assert!(!dibujo.contains(RESALTADO_ANSI));
+    }
+
+    #[test]
+    fn test_colores_de_enemigos_segun_vida() {
+        let mapa: Mapa = "F1 F2 F3".parse().unwrap();
+        assert_eq!(
+            dibujar(&mapa, &HashSet::new()),
+            "\x1b[1;31mF1\x1b[0m \x1b[1;93mF2\x1b[0m \x1b[1;32mF3\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_resaltar_ultima_detonacion() {
+        let mut mapa: Mapa = "F2 B1 _\n_ _ _\n_ _ B1".parse().unwrap();
+        let eventos = turno::jugar_turno_con_eventos(&mut mapa, 1, 0).unwrap();
+        let resaltadas = casillas_alcanzadas(&eventos);
+        assert_eq!(
+            resaltadas,
+            HashSet::from([
+                Coordenada { x: 0, y: 0 },
+                Coordenada { x: 1, y: 0 },
+                Coordenada { x: 2, y: 0 },
+                Coordenada { x: 1, y: 1 },
+            ])
+        );
+        let dibujo = dibujar(&mapa, &resaltadas);
+        let primera_fila = dibujo.lines().next().unwrap();
+        assert_eq!(primera_fila.matches(RESALTADO_ANSI).count(), 3);
+        assert!(!dibujo.lines().nth(2).unwrap().contains(RESALTADO_ANSI));
+        assert_eq!(sin_colores(primera_fila), "F1 .  . ");
+    }
+}
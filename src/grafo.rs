@@ -0,0 +1,199 @@
+use crate::modelo::{bomba::Bomba, coordenada::Coordenada, mapa::Mapa, tile::Tile};
+use crate::turno;
+use std::collections::{HashSet, VecDeque};
+
+/// Grafo dirigido de reacciones en cadena: hay una arista de A a B si la explosion de la bomba A alcanza a la bomba B.
+/// Las bombas se guardan en el orden en el que aparecen en el mapa, recorriendolo por filas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrafoCadena {
+    pub bombas: Vec<Bomba>,
+    pub aristas: Vec<(Coordenada, Coordenada)>,
+}
+
+/// Construye el grafo de reacciones en cadena del mapa.
+/// El alcance de cada bomba se calcula sobre el mapa original, incluyendo los desvios.
+pub fn construir_grafo(mapa: &Mapa) -> GrafoCadena {
+    let bombas: Vec<Bomba> = mapa
+        .tiles
+        .iter()
+        .flatten()
+        .filter_map(|tile| match tile {
+            Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) => Some(bomba.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut aristas = Vec::new();
+    for bomba in bombas.iter() {
+        let mut destinos = HashSet::new();
+        for tile in turno::buscar_tiles(mapa, bomba.x, bomba.y, bomba.clone()) {
+            if let Some(Tile::BombaNormal(_)) | Some(Tile::BombaEspecial(_)) =
+                mapa.obtener_tile(tile.x, tile.y)
+            {
+                if tile != bomba.coordenadas() && destinos.insert(tile) {
+                    aristas.push((bomba.coordenadas(), tile));
+                }
+            }
+        }
+    }
+    GrafoCadena { bombas, aristas }
+}
+
+/// Devuelve el identificador de una bomba en los formatos exportados: "x,y".
+fn identificador(coordenada: &Coordenada) -> String {
+    format!("{},{}", coordenada.x, coordenada.y)
+}
+
+impl GrafoCadena {
+    /// Devuelve las bombas que explotan si se detona la bomba en la posicion dada, incluida ella misma.
+    /// El orden es el de una busqueda a lo ancho desde esa bomba.
+    pub fn detonadas_desde(&self, inicio: Coordenada) -> Vec<Coordenada> {
+        let mut detonadas = vec![inicio];
+        let mut visitadas = HashSet::from([inicio]);
+        let mut pendientes = VecDeque::from([inicio]);
+        while let Some(actual) = pendientes.pop_front() {
+            for (origen, destino) in self.aristas.iter() {
+                if *origen == actual && visitadas.insert(*destino) {
+                    detonadas.push(*destino);
+                    pendientes.push_back(*destino);
+                }
+            }
+        }
+        detonadas
+    }
+
+    /// Devuelve la bomba que, detonada sola, hace explotar a la mayor cantidad de bombas, junto con esa cantidad.
+    /// Ante un empate devuelve la primera en el orden del mapa. Si no hay bombas devuelve None.
+    pub fn mejor_detonacion(&self) -> Option<(Coordenada, usize)> {
+        let mut mejor: Option<(Coordenada, usize)> = None;
+        for bomba in self.bombas.iter() {
+            let cantidad = self.detonadas_desde(bomba.coordenadas()).len();
+            let supera = match mejor {
+                Some((_, maxima)) => cantidad > maxima,
+                None => true,
+            };
+            if supera {
+                mejor = Some((bomba.coordenadas(), cantidad));
+            }
+        }
+        mejor
+    }
+
+    /// Exporta el grafo en formato Graphviz DOT.
+    /// Cada nodo se identifica por "x,y" y se etiqueta con el tipo y el radio de la bomba.
+    pub fn a_dot(&self) -> String {
+        let mut dot = String::from("digraph cadena {\n");
+        for bomba in self.bombas.iter() {
+            let tipo = if bomba.especial { 'S' } else { 'B' };
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}{} ({})\"];\n",
+                identificador(&bomba.coordenadas()),
+                tipo,
+                bomba.radio,
+                identificador(&bomba.coordenadas())
+            ));
+        }
+        for (origen, destino) in self.aristas.iter() {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\";\n",
+                identificador(origen),
+                identificador(destino)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Exporta el grafo en formato JSON, con una lista de bombas y una de aristas.
+    pub fn a_json(&self) -> String {
+        let bombas: Vec<String> = self
+            .bombas
+            .iter()
+            .map(|bomba| {
+                format!(
+                    "{{\"x\":{},\"y\":{},\"radio\":{},\"especial\":{}}}",
+                    bomba.x, bomba.y, bomba.radio, bomba.especial
+                )
+            })
+            .collect();
+        let aristas: Vec<String> = self
+            .aristas
+            .iter()
+            .map(|(origen, destino)| {
+                format!(
+                    "{{\"origen\":{{\"x\":{},\"y\":{}}},\"destino\":{{\"x\":{},\"y\":{}}}}}",
+                    origen.x, origen.y, destino.x, destino.y
+                )
+            })
+            .collect();
+        format!(
+            "{{\"bombas\":[{}],\"aristas\":[{}]}}\n",
+            bombas.join(","),
+            aristas.join(",")
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bomberman_game::transformar_a_mapa;
+
+    fn mapa_en_fila() -> Mapa {
+        Mapa {
+            ancho: 4,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 1, false)),
+                Tile::BombaNormal(Bomba::crear(1, 0, 1, false)),
+                Tile::Vacio,
+                Tile::BombaEspecial(Bomba::crear(3, 0, 2, true)),
+            ]],
+        }
+    }
+
+    #[test]
+    fn test_construir_grafo() {
+        let grafo = construir_grafo(&mapa_en_fila());
+        let (a, b, c) = (
+            Coordenada { x: 0, y: 0 },
+            Coordenada { x: 1, y: 0 },
+            Coordenada { x: 3, y: 0 },
+        );
+        assert_eq!(grafo.bombas.len(), 3);
+        assert_eq!(grafo.aristas, vec![(a, b), (b, a), (c, b)]);
+        assert_eq!(grafo.detonadas_desde(c), vec![c, b, a]);
+        assert_eq!(grafo.mejor_detonacion(), Some((c, 3)));
+    }
+
+    #[test]
+    fn test_exportar() {
+        let grafo = construir_grafo(&mapa_en_fila());
+        let dot = grafo.a_dot();
+        assert!(dot.starts_with("digraph cadena {\n"));
+        assert!(dot.contains("    \"3,0\" [label=\"S2 (3,0)\"];\n"));
+        assert!(dot.contains("    \"3,0\" -> \"1,0\";\n"));
+        assert_eq!(
+            construir_grafo(&Mapa {
+                ancho: 1,
+                alto: 1,
+                tiles: vec![vec![Tile::BombaNormal(Bomba::crear(0, 0, 1, false))]],
+            })
+            .a_json(),
+            "{\"bombas\":[{\"x\":0,\"y\":0,\"radio\":1,\"especial\":false}],\"aristas\":[]}\n"
+        );
+        assert!(grafo
+            .a_json()
+            .contains("{\"origen\":{\"x\":0,\"y\":0},\"destino\":{\"x\":1,\"y\":0}}"));
+    }
+
+    #[test]
+    fn test_grafo_con_desvio() {
+        let mapa = transformar_a_mapa("mapas/mapa_3.txt").unwrap();
+        let grafo = construir_grafo(&mapa);
+        assert!(grafo
+            .aristas
+            .contains(&(Coordenada { x: 0, y: 4 }, Coordenada { x: 0, y: 2 })));
+        assert_eq!(grafo.detonadas_desde(Coordenada { x: 2, y: 4 }).len(), 3);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::azar::Azar;
+use crate::modelo::{
+    coordenada::Coordenada, direccion::Direccion, enemigo::Enemigo, mapa::Mapa,
+    movimiento::Movimiento, tile::Tile,
+};
+use crate::turno;
+use std::collections::{HashSet, VecDeque};
+
+/// Lo que un enemigo puede ver al decidir su movimiento.
+pub struct Entorno<'a> {
+    pub mapa: &'a Mapa,
+    /// Posicion del jugador, o None si no hay jugador o fue eliminado.
+    pub jugador: Option<Coordenada>,
+    pub azar: &'a mut Azar,
+}
+
+/// Forma de decidir hacia donde se mueve un enemigo en cada tick.
+/// Para agregar un tipo de enemigo se implementa este trait, se agrega su variante a Movimiento y se la asocia en comportamiento.
+pub trait Comportamiento {
+    /// Devuelve la direccion en la que quiere moverse el enemigo, o None si se queda quieto.
+    /// La partida solo lo mueve si la casilla de destino esta libre.
+    fn elegir(&self, enemigo: &Enemigo, entorno: &mut Entorno) -> Option<Direccion>;
+}
+
+/// Se mueve a una casilla libre vecina elegida al azar. Si no hay ninguna, se queda quieto.
+pub struct Aleatorio;
+
+/// Sigue en la direccion de su patrulla; si esta bloqueada, se da vuelta.
+pub struct Patrulla;
+
+/// Da el primer paso del camino mas corto hacia el jugador.
+pub struct Perseguidor;
+
+/// Si esta al alcance de alguna bomba, da el primer paso del camino mas corto hacia una casilla fuera de su alcance.
+pub struct Huidizo;
+
+/// Devuelve el comportamiento que corresponde al movimiento, o None si el enemigo no se mueve.
+pub fn comportamiento(movimiento: &Movimiento) -> Option<&'static dyn Comportamiento> {
+    match movimiento {
+        Movimiento::Quieto => None,
+        Movimiento::Aleatorio => Some(&Aleatorio),
+        Movimiento::Patrulla(_) => Some(&Patrulla),
+        Movimiento::Perseguidor => Some(&Perseguidor),
+        Movimiento::Huidizo => Some(&Huidizo),
+    }
+}
+
+/// Devuelve si un enemigo puede moverse a la casilla: solo a las casillas vacias.
+/// A diferencia del jugador, no camina sobre desvios porque ocuparia su tile.
+pub fn libre(mapa: &Mapa, coordenada: &Coordenada) -> bool {
+    matches!(
+        mapa.obtener_tile(coordenada.x, coordenada.y),
+        Some(Tile::Vacio)
+    )
+}
+
+/// Devuelve las casillas libres vecinas a desde, junto con la direccion para llegar a cada una.
+fn vecinas_libres(mapa: &Mapa, desde: &Coordenada) -> Vec<(Direccion, Coordenada)> {
+    Direccion::TODAS
+        .iter()
+        .filter_map(|direccion| {
+            mapa.vecina(desde.x, desde.y, *direccion)
+                .filter(|vecina| libre(mapa, vecina))
+                .map(|vecina| (*direccion, vecina))
+        })
+        .collect()
+}
+
+/// Busca a lo ancho, caminando solo por casillas libres, la casilla mas cercana a desde que cumple es_destino.
+/// Devuelve la direccion del primer paso hacia ella, o None si no hay ninguna alcanzable o si desde ya la cumple.
+pub fn primer_paso(
+    mapa: &Mapa,
+    desde: &Coordenada,
+    es_destino: impl Fn(&Coordenada) -> bool,
+) -> Option<Direccion> {
+    let mut visitadas = HashSet::from([*desde]);
+    let mut pendientes: VecDeque<(Coordenada, Direccion)> = VecDeque::new();
+    for (direccion, vecina) in vecinas_libres(mapa, desde) {
+        visitadas.insert(vecina);
+        pendientes.push_back((vecina, direccion));
+    }
+    while let Some((actual, primera)) = pendientes.pop_front() {
+        if es_destino(&actual) {
+            return Some(primera);
+        }
+        for (_, vecina) in vecinas_libres(mapa, &actual) {
+            if visitadas.insert(vecina) {
+                pendientes.push_back((vecina, primera));
+            }
+        }
+    }
+    None
+}
+
+/// Devuelve las casillas que alcanzaria la explosion de alguna de las bombas del mapa, incluidas las de las bombas.
+pub fn casillas_en_peligro(mapa: &Mapa) -> HashSet<Coordenada> {
+    let mut peligro = HashSet::new();
+    for tile in mapa.tiles.iter().flatten() {
+        if let Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) = tile {
+            peligro.insert(bomba.coordenadas());
+            peligro.extend(turno::buscar_tiles(mapa, bomba.x, bomba.y, bomba.clone()));
+        }
+    }
+    peligro
+}
+
+impl Comportamiento for Aleatorio {
+    fn elegir(&self, enemigo: &Enemigo, entorno: &mut Entorno) -> Option<Direccion> {
+        let opciones = vecinas_libres(entorno.mapa, &enemigo.coordenadas());
+        if opciones.is_empty() {
+            return None;
+        }
+        Some(opciones[entorno.azar.indice(opciones.len())].0)
+    }
+}
+
+impl Comportamiento for Patrulla {
+    fn elegir(&self, enemigo: &Enemigo, entorno: &mut Entorno) -> Option<Direccion> {
+        let direccion = match enemigo.movimiento {
+            Movimiento::Patrulla(direccion) => direccion,
+            _ => return None,
+        };
+        [direccion, direccion.opuesta()]
+            .into_iter()
+            .find(|direccion| {
+                entorno
+                    .mapa
+                    .vecina(enemigo.x, enemigo.y, *direccion)
+                    .is_some_and(|vecina| libre(entorno.mapa, &vecina))
+            })
+    }
+}
+
+impl Comportamiento for Perseguidor {
+    fn elegir(&self, enemigo: &Enemigo, entorno: &mut Entorno) -> Option<Direccion> {
+        let jugador = entorno.jugador?;
+        primer_paso(entorno.mapa, &enemigo.coordenadas(), |casilla| {
+            *casilla == jugador
+        })
+    }
+}
+
+impl Comportamiento for Huidizo {
+    fn elegir(&self, enemigo: &Enemigo, entorno: &mut Entorno) -> Option<Direccion> {
+        let peligro = casillas_en_peligro(entorno.mapa);
+        if !peligro.contains(&enemigo.coordenadas()) {
+            return None;
+        }
+        primer_paso(entorno.mapa, &enemigo.coordenadas(), |casilla| {
+            !peligro.contains(casilla)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Devuelve la direccion que elige el enemigo en (x, y) del mapa.
+    fn elegir(mapa: &str, x: usize, y: usize, jugador: Option<Coordenada>) -> Option<Direccion> {
+        let mapa: Mapa = mapa.parse().unwrap();
+        let enemigo = match mapa.obtener_tile(x, y) {
+            Some(Tile::Enemigo(enemigo)) => enemigo.clone(),
+            _ => panic!("no hay enemigo en ({}, {})", x, y),
+        };
+        let mut azar = Azar::crear(1);
+        let mut entorno = Entorno {
+            mapa: &mapa,
+            jugador,
+            azar: &mut azar,
+        };
+        comportamiento(&enemigo.movimiento)?.elegir(&enemigo, &mut entorno)
+    }
+
+    #[test]
+    fn test_quieto_no_se_mueve() {
+        assert_eq!(elegir("_ F1 _", 1, 0, None), None);
+    }
+
+    #[test]
+    fn test_aleatorio_elige_una_casilla_libre() {
+        assert_eq!(
+            elegir("W F1a _\n_ B1 _", 1, 0, None),
+            Some(Direccion::Derecha)
+        );
+        assert_eq!(elegir("W F1a R\n_ B1 _", 1, 0, None), None);
+    }
+
+    #[test]
+    fn test_patrulla_se_da_vuelta_al_chocar() {
+        assert_eq!(elegir("_ F1h _", 1, 0, None), Some(Direccion::Derecha));
+        assert_eq!(elegir("_ F1h W", 1, 0, None), Some(Direccion::Izquierda));
+        assert_eq!(elegir("R F1h W", 1, 0, None), None);
+        assert_eq!(elegir("_\nF1v\nDU", 0, 1, None), Some(Direccion::Arriba));
+    }
+
+    #[test]
+    fn test_perseguidor_rodea_las_paredes() {
+        let mapa = "F1p W _\n_ W _\n_ _ _";
+        assert_eq!(
+            elegir(mapa, 0, 0, Some(Coordenada { x: 2, y: 0 })),
+            Some(Direccion::Abajo)
+        );
+        assert_eq!(elegir(mapa, 0, 0, None), None);
+        assert_eq!(
+            elegir("F1p W _", 0, 0, Some(Coordenada { x: 2, y: 0 })),
+            None
+        );
+    }
+
+    #[test]
+    fn test_huidizo_sale_del_alcance_de_las_bombas() {
+        assert_eq!(elegir("B1 F1e _", 1, 0, None), Some(Direccion::Derecha));
+        assert_eq!(
+            elegir("B2 F1e _\n_ _ _", 1, 0, None),
+            Some(Direccion::Abajo)
+        );
+        assert_eq!(elegir("B1 _ F1e", 2, 0, None), None);
+    }
+}
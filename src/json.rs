@@ -0,0 +1,1089 @@
+use crate::azar::Azar;
+use crate::modelo::{
+    bomba::{Bomba, DMG_POR_DEFECTO},
+    coordenada::Coordenada,
+    desvio::Desvio,
+    direccion::Direccion,
+    enemigo::Enemigo,
+    error::ParseError,
+    jugador::{Jugador, CAPACIDAD_POR_DEFECTO, RADIO_POR_DEFECTO},
+    mapa::Mapa,
+    movimiento::Movimiento,
+    obstaculo::Obstaculo,
+    premio::Premio,
+    tile::Tile,
+};
+use crate::partida::{ModoCadena, Partida};
+use std::fmt;
+
+/// Cantidad maxima de listas u objetos anidados que se aceptan al parsear.
+/// Evita desbordar la pila con entradas maliciosas como [[[[...]]]].
+const PROFUNDIDAD_MAXIMA: usize = 128;
+
+/// Valor JSON. Los objetos conservan el orden de sus claves.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Valor {
+    Nulo,
+    Booleano(bool),
+    Numero(f64),
+    Texto(String),
+    Lista(Vec<Valor>),
+    Objeto(Vec<(String, Valor)>),
+}
+
+/// Error de sintaxis JSON, con la posicion en bytes donde ocurrio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorJson {
+    pub posicion: usize,
+    pub mensaje: String,
+}
+
+impl fmt::Display for ErrorJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "posicion {}: {}", self.posicion, self.mensaje)
+    }
+}
+
+impl Valor {
+    /// Devuelve el valor de la clave si el valor es un objeto que la tiene.
+    pub fn campo(&self, clave: &str) -> Option<&Valor> {
+        match self {
+            Valor::Objeto(campos) => campos
+                .iter()
+                .find(|(nombre, _)| nombre == clave)
+                .map(|(_, valor)| valor),
+            _ => None,
+        }
+    }
+
+    /// Devuelve el numero si el valor es un entero no negativo que entra en un u64.
+    pub fn como_entero(&self) -> Option<u64> {
+        match self {
+            Valor::Numero(n) if *n >= 0.0 && n.fract() == 0.0 && *n <= u64::MAX as f64 => {
+                Some(*n as u64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Escribe un texto como string JSON, escapando las comillas, las barras y los caracteres de control.
+fn escribir_texto(f: &mut fmt::Formatter<'_>, texto: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in texto.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+/// Muestra el valor como JSON compacto, sin espacios.
+impl fmt::Display for Valor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Valor::Nulo => write!(f, "null"),
+            Valor::Booleano(b) => write!(f, "{}", b),
+            Valor::Numero(n) if n.is_finite() => write!(f, "{}", n),
+            Valor::Numero(_) => write!(f, "null"),
+            Valor::Texto(texto) => escribir_texto(f, texto),
+            Valor::Lista(valores) => {
+                write!(f, "[")?;
+                for (i, valor) in valores.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", valor)?;
+                }
+                write!(f, "]")
+            }
+            Valor::Objeto(campos) => {
+                write!(f, "{{")?;
+                for (i, (clave, valor)) in campos.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    escribir_texto(f, clave)?;
+                    write!(f, ":{}", valor)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Parser de JSON por descenso recursivo sobre los bytes del texto.
+struct Parser<'a> {
+    texto: &'a str,
+    posicion: usize,
+    profundidad: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, mensaje: &str) -> Result<T, ErrorJson> {
+        Err(ErrorJson {
+            posicion: self.posicion,
+            mensaje: mensaje.to_string(),
+        })
+    }
+
+    fn siguiente_byte(&self) -> Option<u8> {
+        self.texto.as_bytes().get(self.posicion).copied()
+    }
+
+    fn saltear_espacios(&mut self) {
+        while let Some(b' ' | b'\n' | b'\r' | b'\t') = self.siguiente_byte() {
+            self.posicion += 1;
+        }
+    }
+
+    /// Consume el texto esperado o devuelve un error si no esta en la posicion actual.
+    fn esperar(&mut self, esperado: &str) -> Result<(), ErrorJson> {
+        if self.texto[self.posicion..].starts_with(esperado) {
+            self.posicion += esperado.len();
+            Ok(())
+        } else {
+            self.error(&format!("se esperaba {}", esperado))
+        }
+    }
+
+    fn parsear_valor(&mut self) -> Result<Valor, ErrorJson> {
+        self.saltear_espacios();
+        match self.siguiente_byte() {
+            Some(b'n') => self.esperar("null").map(|_| Valor::Nulo),
+            Some(b't') => self.esperar("true").map(|_| Valor::Booleano(true)),
+            Some(b'f') => self.esperar("false").map(|_| Valor::Booleano(false)),
+            Some(b'"') => self.parsear_texto().map(Valor::Texto),
+            Some(b'[') => self.parsear_lista(),
+            Some(b'{') => self.parsear_objeto(),
+            Some(b'-' | b'0'..=b'9') => self.parsear_numero(),
+            Some(_) => self.error("valor inesperado"),
+            None => self.error("fin del texto inesperado"),
+        }
+    }
+
+    fn parsear_numero(&mut self) -> Result<Valor, ErrorJson> {
+        let inicio = self.posicion;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.siguiente_byte() {
+            self.posicion += 1;
+        }
+        match self.texto[inicio..self.posicion].parse::<f64>() {
+            Ok(numero) if numero.is_finite() => Ok(Valor::Numero(numero)),
+            _ => {
+                self.posicion = inicio;
+                self.error("numero invalido")
+            }
+        }
+    }
+
+    /// Lee los 4 digitos hexadecimales de un escape \uXXXX.
+    fn parsear_hexa(&mut self) -> Result<u32, ErrorJson> {
+        let digitos = match self.texto.get(self.posicion..self.posicion + 4) {
+            Some(digitos) => digitos,
+            None => return self.error("escape \\u incompleto"),
+        };
+        match u32::from_str_radix(digitos, 16) {
+            Ok(codigo) if digitos.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                self.posicion += 4;
+                Ok(codigo)
+            }
+            _ => self.error("escape \\u invalido"),
+        }
+    }
+
+    fn parsear_escape(&mut self) -> Result<char, ErrorJson> {
+        let escapado = match self.siguiente_byte() {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.posicion += 1;
+                let mut codigo = self.parsear_hexa()?;
+                if (0xD800..0xDC00).contains(&codigo) {
+                    self.esperar("\\u")?;
+                    let bajo = self.parsear_hexa()?;
+                    if !(0xDC00..0xE000).contains(&bajo) {
+                        return self.error("par sustituto invalido");
+                    }
+                    codigo = 0x10000 + ((codigo - 0xD800) << 10) + (bajo - 0xDC00);
+                }
+                return match char::from_u32(codigo) {
+                    Some(c) => Ok(c),
+                    None => self.error("caracter unicode invalido"),
+                };
+            }
+            _ => return self.error("escape invalido"),
+        };
+        self.posicion += 1;
+        Ok(escapado)
+    }
+
+    fn parsear_texto(&mut self) -> Result<String, ErrorJson> {
+        self.esperar("\"")?;
+        let mut texto = String::new();
+        loop {
+            let c = match self.texto[self.posicion..].chars().next() {
+                Some(c) => c,
+                None => return self.error("string sin terminar"),
+            };
+            match c {
+                '"' => {
+                    self.posicion += 1;
+                    return Ok(texto);
+                }
+                '\\' => {
+                    self.posicion += 1;
+                    texto.push(self.parsear_escape()?);
+                }
+                c if (c as u32) < 0x20 => return self.error("caracter de control en un string"),
+                c => {
+                    self.posicion += c.len_utf8();
+                    texto.push(c);
+                }
+            }
+        }
+    }
+
+    /// Entra a una lista u objeto, fallando si se supera la profundidad maxima.
+    fn entrar(&mut self, apertura: &str) -> Result<(), ErrorJson> {
+        if self.profundidad == PROFUNDIDAD_MAXIMA {
+            return self.error("demasiados niveles anidados");
+        }
+        self.profundidad += 1;
+        self.esperar(apertura)?;
+        self.saltear_espacios();
+        Ok(())
+    }
+
+    /// Consume una coma y devuelve true, o consume el cierre y devuelve false.
+    fn hay_otro(&mut self, cierre: u8) -> Result<bool, ErrorJson> {
+        self.saltear_espacios();
+        match self.siguiente_byte() {
+            Some(b',') => {
+                self.posicion += 1;
+                Ok(true)
+            }
+            Some(b) if b == cierre => {
+                self.posicion += 1;
+                self.profundidad -= 1;
+                Ok(false)
+            }
+            _ => self.error(&format!("se esperaba , o {}", cierre as char)),
+        }
+    }
+
+    fn parsear_lista(&mut self) -> Result<Valor, ErrorJson> {
+        self.entrar("[")?;
+        let mut valores = Vec::new();
+        if self.siguiente_byte() == Some(b']') {
+            self.posicion += 1;
+            self.profundidad -= 1;
+            return Ok(Valor::Lista(valores));
+        }
+        loop {
+            valores.push(self.parsear_valor()?);
+            if !self.hay_otro(b']')? {
+                return Ok(Valor::Lista(valores));
+            }
+        }
+    }
+
+    fn parsear_objeto(&mut self) -> Result<Valor, ErrorJson> {
+        self.entrar("{")?;
+        let mut campos = Vec::new();
+        if self.siguiente_byte() == Some(b'}') {
+            self.posicion += 1;
+            self.profundidad -= 1;
+            return Ok(Valor::Objeto(campos));
+        }
+        loop {
+            self.saltear_espacios();
+            let clave = self.parsear_texto()?;
+            self.saltear_espacios();
+            self.esperar(":")?;
+            campos.push((clave, self.parsear_valor()?));
+            if !self.hay_otro(b'}')? {
+                return Ok(Valor::Objeto(campos));
+            }
+        }
+    }
+}
+
+/// Parsea un texto JSON completo. Despues del valor solo puede haber espacios.
+pub fn parsear(texto: &str) -> Result<Valor, ErrorJson> {
+    let mut parser = Parser {
+        texto,
+        posicion: 0,
+        profundidad: 0,
+    };
+    let valor = parser.parsear_valor()?;
+    parser.saltear_espacios();
+    if parser.posicion != texto.len() {
+        return parser.error("texto sobrante despues del valor");
+    }
+    Ok(valor)
+}
+
+/// Devuelve si el texto parece un mapa en JSON en lugar de un mapa en formato texto.
+/// Ningun token del formato texto empieza con {, por lo que no hay ambiguedad.
+pub fn es_json(texto: &str) -> bool {
+    texto.trim_start().starts_with('{')
+}
+
+fn numero(n: impl Into<f64>) -> Valor {
+    Valor::Numero(n.into())
+}
+
+fn texto(s: &str) -> Valor {
+    Valor::Texto(s.to_string())
+}
+
+fn objeto(campos: Vec<(&str, Valor)>) -> Valor {
+    Valor::Objeto(
+        campos
+            .into_iter()
+            .map(|(clave, valor)| (clave.to_string(), valor))
+            .collect(),
+    )
+}
+
+/// Devuelve la coordenada como objeto JSON: {"x":0,"y":0}.
+pub fn coordenada_a_valor(coordenada: &Coordenada) -> Valor {
+    objeto(vec![
+        ("x", numero(coordenada.x as f64)),
+        ("y", numero(coordenada.y as f64)),
+    ])
+}
+
+/// Devuelve el nombre del movimiento en JSON y, si es una patrulla, la direccion en la que se esta moviendo.
+fn movimiento_a_campos(movimiento: &Movimiento) -> Vec<(&'static str, Valor)> {
+    match movimiento {
+        Movimiento::Quieto => vec![],
+        Movimiento::Aleatorio => vec![("movimiento", texto("aleatorio"))],
+        Movimiento::Patrulla(direccion) => vec![
+            ("movimiento", texto("patrulla")),
+            ("direccion", texto(&direccion.a_char().to_string())),
+        ],
+        Movimiento::Perseguidor => vec![("movimiento", texto("perseguidor"))],
+        Movimiento::Huidizo => vec![("movimiento", texto("huidizo"))],
+    }
+}
+
+/// Devuelve el nombre del premio en JSON.
+fn nombre_premio(premio: &Premio) -> &'static str {
+    match premio {
+        Premio::BombaExtra => "bomba_extra",
+        Premio::MasRadio => "mas_radio",
+        Premio::BombaEspecial => "bomba_especial",
+    }
+}
+
+/// Devuelve el premio indicado por el campo premio, o None si no esta o es null.
+fn campo_premio(valor: &Valor, donde: &str) -> Result<Option<Premio>, String> {
+    match valor.campo("premio") {
+        None | Some(Valor::Nulo) => Ok(None),
+        Some(Valor::Texto(nombre)) => match nombre.as_str() {
+            "bomba_extra" => Ok(Some(Premio::BombaExtra)),
+            "mas_radio" => Ok(Some(Premio::MasRadio)),
+            "bomba_especial" => Ok(Some(Premio::BombaEspecial)),
+            _ => Err(format!("{}: premio desconocido: {}", donde, nombre)),
+        },
+        Some(_) => Err(format!("{}: premio debe ser un texto", donde)),
+    }
+}
+
+/// Devuelve el tile como objeto JSON. La posicion no se incluye, porque la indica el lugar en la grilla.
+/// Las bombas que ya impactaron a un enemigo se ordenan por fila y columna para que la salida sea estable.
+pub fn tile_a_valor(tile: &Tile) -> Valor {
+    match tile {
+        Tile::Enemigo(enemigo) => {
+            let mut impactadas: Vec<&Coordenada> = enemigo.bombas_impactadas.iter().collect();
+            impactadas.sort_by_key(|coordenada| (coordenada.y, coordenada.x));
+            let mut campos = vec![
+                ("tipo", texto("enemigo")),
+                ("vida", numero(enemigo.vida)),
+                (
+                    "bombas_impactadas",
+                    Valor::Lista(impactadas.into_iter().map(coordenada_a_valor).collect()),
+                ),
+            ];
+            campos.append(&mut movimiento_a_campos(&enemigo.movimiento));
+            objeto(campos)
+        }
+        Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) => objeto(vec![
+            ("tipo", texto("bomba")),
+            ("radio", numero(bomba.radio)),
+            ("especial", Valor::Booleano(bomba.especial)),
+            ("dmg", numero(bomba.dmg)),
+            (
+                "mecha",
+                match bomba.mecha {
+                    Some(mecha) => numero(mecha),
+                    None => Valor::Nulo,
+                },
+            ),
+        ]),
+        Tile::Piedra(piedra) => match piedra.premio {
+            Some(premio) => objeto(vec![
+                ("tipo", texto("piedra")),
+                ("premio", texto(nombre_premio(&premio))),
+            ]),
+            None => objeto(vec![("tipo", texto("piedra"))]),
+        },
+        Tile::Pared(_) => objeto(vec![("tipo", texto("pared"))]),
+        Tile::Desvio(desvio) => objeto(vec![
+            ("tipo", texto("desvio")),
+            ("direccion", texto(&desvio.char_direccion().to_string())),
+        ]),
+        Tile::Jugador(jugador) => objeto(vec![
+            ("tipo", texto("jugador")),
+            ("capacidad", numero(jugador.capacidad)),
+            ("radio", numero(jugador.radio)),
+            ("especial", Valor::Booleano(jugador.especial)),
+        ]),
+        Tile::Premio(premio) => objeto(vec![
+            ("tipo", texto("premio")),
+            ("premio", texto(nombre_premio(premio))),
+        ]),
+        Tile::Vacio => objeto(vec![("tipo", texto("vacio"))]),
+    }
+}
+
+/// Devuelve el mapa como objeto JSON, con su ancho, su alto y una lista de filas de tiles.
+pub fn mapa_a_valor(mapa: &Mapa) -> Valor {
+    objeto(vec![
+        ("ancho", numero(mapa.ancho as f64)),
+        ("alto", numero(mapa.alto as f64)),
+        (
+            "tiles",
+            Valor::Lista(
+                mapa.tiles
+                    .iter()
+                    .map(|fila| Valor::Lista(fila.iter().map(tile_a_valor).collect()))
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+/// Devuelve el mapa en JSON, en una sola linea terminada en un salto de linea.
+pub fn mapa_a_json(mapa: &Mapa) -> String {
+    format!("{}\n", mapa_a_valor(mapa))
+}
+
+/// Devuelve el campo pedido como entero, o un error indicando que falta o no es valido.
+fn campo_entero(valor: &Valor, clave: &str, donde: &str) -> Result<u64, String> {
+    match valor.campo(clave) {
+        None => Err(format!("{}: falta el campo {}", donde, clave)),
+        Some(campo) => campo
+            .como_entero()
+            .ok_or_else(|| format!("{}: {} debe ser un entero no negativo", donde, clave)),
+    }
+}
+
+/// Igual que campo_entero, pero ademas exige que el valor entre en un u32 y no sea 0.
+fn campo_positivo(valor: &Valor, clave: &str, donde: &str) -> Result<u32, String> {
+    match u32::try_from(campo_entero(valor, clave, donde)?) {
+        Ok(0) => Err(format!("{}: {} no puede ser 0", donde, clave)),
+        Ok(n) => Ok(n),
+        Err(_) => Err(format!("{}: {} es demasiado grande", donde, clave)),
+    }
+}
+
+/// Devuelve la coordenada representada por el objeto {"x":..,"y":..}.
+pub fn valor_a_coordenada(valor: &Valor, donde: &str) -> Result<Coordenada, String> {
+    let x = campo_entero(valor, "x", donde)?;
+    let y = campo_entero(valor, "y", donde)?;
+    match (usize::try_from(x), usize::try_from(y)) {
+        (Ok(x), Ok(y)) => Ok(Coordenada { x, y }),
+        _ => Err(format!("{}: coordenada fuera de rango", donde)),
+    }
+}
+
+/// Devuelve la direccion indicada por el campo direccion: U, D, L o R.
+fn campo_direccion(valor: &Valor, donde: &str) -> Result<Direccion, String> {
+    let direccion = match valor.campo("direccion") {
+        Some(Valor::Texto(direccion)) => {
+            let mut caracteres = direccion.chars();
+            match (caracteres.next(), caracteres.next()) {
+                (Some(c), None) => Direccion::desde_char(c),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    direccion.ok_or_else(|| format!("{}: direccion debe ser U, D, L o R", donde))
+}
+
+/// Devuelve el movimiento de un enemigo a partir de su objeto JSON. Si no tiene el campo movimiento, esta quieto.
+fn valor_a_movimiento(valor: &Valor, donde: &str) -> Result<Movimiento, String> {
+    let movimiento = match valor.campo("movimiento") {
+        None => return Ok(Movimiento::Quieto),
+        Some(Valor::Texto(movimiento)) => movimiento.as_str(),
+        Some(_) => return Err(format!("{}: movimiento debe ser un texto", donde)),
+    };
+    match movimiento {
+        "quieto" => Ok(Movimiento::Quieto),
+        "aleatorio" => Ok(Movimiento::Aleatorio),
+        "patrulla" => Ok(Movimiento::Patrulla(campo_direccion(valor, donde)?)),
+        "perseguidor" => Ok(Movimiento::Perseguidor),
+        "huidizo" => Ok(Movimiento::Huidizo),
+        _ => Err(format!("{}: movimiento desconocido: {}", donde, movimiento)),
+    }
+}
+
+/// Crea el tile en la posicion dada a partir de su objeto JSON.
+/// Aplica las mismas restricciones que el formato texto: vida, radio, daño y capacidad mayores a 0.
+pub fn valor_a_tile(valor: &Valor, x: usize, y: usize) -> Result<Tile, String> {
+    let donde = format!("fila {}, columna {}", y, x);
+    let tipo = match valor.campo("tipo") {
+        Some(Valor::Texto(tipo)) => tipo.as_str(),
+        _ => return Err(format!("{}: falta el campo tipo", donde)),
+    };
+    match tipo {
+        "enemigo" => {
+            let mut enemigo = Enemigo::crear(x, y, campo_positivo(valor, "vida", &donde)?);
+            enemigo.movimiento = valor_a_movimiento(valor, &donde)?;
+            match valor.campo("bombas_impactadas") {
+                None => {}
+                Some(Valor::Lista(impactadas)) => {
+                    for impactada in impactadas {
+                        let bomba = valor_a_coordenada(impactada, &donde)?;
+                        enemigo.recibir_impacto(bomba.x, bomba.y);
+                    }
+                }
+                Some(_) => return Err(format!("{}: bombas_impactadas debe ser una lista", donde)),
+            }
+            Ok(Tile::Enemigo(enemigo))
+        }
+        "bomba" => {
+            let radio = campo_positivo(valor, "radio", &donde)?;
+            let especial = match valor.campo("especial") {
+                None => false,
+                Some(Valor::Booleano(especial)) => *especial,
+                Some(_) => return Err(format!("{}: especial debe ser true o false", donde)),
+            };
+            let dmg = match valor.campo("dmg") {
+                None => DMG_POR_DEFECTO,
+                Some(_) => campo_positivo(valor, "dmg", &donde)?,
+            };
+            let mut bomba = Bomba::crear_con_dmg(x, y, radio, especial, dmg);
+            bomba.mecha = match valor.campo("mecha") {
+                None | Some(Valor::Nulo) => None,
+                Some(_) => Some(campo_positivo(valor, "mecha", &donde)?),
+            };
+            if especial {
+                Ok(Tile::BombaEspecial(bomba))
+            } else {
+                Ok(Tile::BombaNormal(bomba))
+            }
+        }
+        "piedra" => {
+            let mut piedra = Obstaculo::crear(x, y, false);
+            piedra.premio = campo_premio(valor, &donde)?;
+            Ok(Tile::Piedra(piedra))
+        }
+        "premio" => match campo_premio(valor, &donde)? {
+            Some(premio) => Ok(Tile::Premio(premio)),
+            None => Err(format!("{}: falta el campo premio", donde)),
+        },
+        "pared" => Ok(Tile::Pared(Obstaculo::crear(x, y, true))),
+        "desvio" => {
+            let direccion = campo_direccion(valor, &donde)?;
+            Ok(Tile::Desvio(Desvio { x, y, direccion }))
+        }
+        "jugador" => {
+            let mut jugador = Jugador::crear(x, y);
+            jugador.capacidad = match valor.campo("capacidad") {
+                None => CAPACIDAD_POR_DEFECTO,
+                Some(_) => campo_positivo(valor, "capacidad", &donde)?,
+            };
+            jugador.radio = match valor.campo("radio") {
+                None => RADIO_POR_DEFECTO,
+                Some(_) => campo_positivo(valor, "radio", &donde)?,
+            };
+            jugador.especial = match valor.campo("especial") {
+                None => false,
+                Some(Valor::Booleano(especial)) => *especial,
+                Some(_) => return Err(format!("{}: especial debe ser true o false", donde)),
+            };
+            Ok(Tile::Jugador(jugador))
+        }
+        "vacio" => Ok(Tile::Vacio),
+        _ => Err(format!("{}: tipo de tile desconocido: {}", donde, tipo)),
+    }
+}
+
+/// Crea un mapa a partir de su objeto JSON.
+/// Las filas deben tener todas la misma cantidad de tiles. Si el objeto indica ancho y alto, deben coincidir con la grilla.
+pub fn valor_a_mapa(valor: &Valor) -> Result<Mapa, ParseError> {
+    let json = |mensaje: String| ParseError::Json { mensaje };
+    let filas = match valor.campo("tiles") {
+        Some(Valor::Lista(filas)) => filas,
+        _ => return Err(json("falta la lista tiles".to_string())),
+    };
+
+    let mut mapa = Mapa::crear();
+    for (y, fila) in filas.iter().enumerate() {
+        let fila = match fila {
+            Valor::Lista(fila) => fila,
+            _ => return Err(json(format!("fila {}: debe ser una lista", y))),
+        };
+        if y == 0 {
+            mapa.ancho = fila.len();
+        }
+        if fila.len() != mapa.ancho {
+            return Err(ParseError::FilaIrregular {
+                fila: y,
+                esperadas: mapa.ancho,
+                encontradas: fila.len(),
+            });
+        }
+        let mut tiles = Vec::new();
+        for (x, tile) in fila.iter().enumerate() {
+            tiles.push(valor_a_tile(tile, x, y).map_err(json)?);
+        }
+        mapa.tiles.push(tiles);
+    }
+    mapa.alto = mapa.tiles.len();
+
+    if mapa.ancho == 0 || mapa.alto == 0 {
+        return Err(ParseError::MapaVacio);
+    }
+    for (clave, esperado) in [("ancho", mapa.ancho), ("alto", mapa.alto)] {
+        if valor.campo(clave).is_some()
+            && campo_entero(valor, clave, "mapa").map_err(json)? != esperado as u64
+        {
+            return Err(json(format!(
+                "{} no coincide con la grilla, que tiene {}",
+                clave, esperado
+            )));
+        }
+    }
+    Ok(mapa)
+}
+
+/// Transforma un texto JSON en un mapa.
+pub fn mapa_desde_json(texto: &str) -> Result<Mapa, ParseError> {
+    let valor = parsear(texto).map_err(|error| ParseError::Json {
+        mensaje: error.to_string(),
+    })?;
+    valor_a_mapa(&valor)
+}
+
+/// Valor del campo formato de una partida guardada. Permite distinguirla de un mapa.
+pub const FORMATO_PARTIDA: &str = "partida";
+/// Version del formato de las partidas guardadas. Se incrementa si cambia de forma incompatible.
+pub const VERSION_PARTIDA: u64 = 1;
+
+/// Devuelve el campo pedido como booleano, o un error si falta o no es true ni false.
+fn campo_booleano(valor: &Valor, clave: &str, donde: &str) -> Result<bool, String> {
+    match valor.campo(clave) {
+        Some(Valor::Booleano(b)) => Ok(*b),
+        _ => Err(format!("{}: {} debe ser true o false", donde, clave)),
+    }
+}
+
+/// Devuelve el jugador de una partida como objeto JSON, con su posicion y si sigue vivo.
+fn jugador_a_valor(jugador: &Jugador) -> Valor {
+    objeto(vec![
+        ("x", numero(jugador.x as f64)),
+        ("y", numero(jugador.y as f64)),
+        ("capacidad", numero(jugador.capacidad)),
+        ("radio", numero(jugador.radio)),
+        ("especial", Valor::Booleano(jugador.especial)),
+        ("vivo", Valor::Booleano(jugador.vivo)),
+    ])
+}
+
+/// Crea el jugador de una partida a partir de su objeto JSON. A diferencia del tile, todos los campos son obligatorios.
+fn valor_a_jugador(valor: &Valor) -> Result<Jugador, String> {
+    let donde = "jugador";
+    let coordenada = valor_a_coordenada(valor, donde)?;
+    let mut jugador = Jugador::crear(coordenada.x, coordenada.y);
+    jugador.capacidad = campo_positivo(valor, "capacidad", donde)?;
+    jugador.radio = campo_positivo(valor, "radio", donde)?;
+    jugador.especial = campo_booleano(valor, "especial", donde)?;
+    jugador.vivo = campo_booleano(valor, "vivo", donde)?;
+    Ok(jugador)
+}
+
+/// Devuelve el modo de cadena como objeto JSON: {"tipo":"instantanea"} o {"tipo":"acortar_mecha","mecha":n}.
+fn modo_cadena_a_valor(modo: &ModoCadena) -> Valor {
+    match modo {
+        ModoCadena::Instantanea => objeto(vec![("tipo", texto("instantanea"))]),
+        ModoCadena::AcortarMecha(mecha) => objeto(vec![
+            ("tipo", texto("acortar_mecha")),
+            ("mecha", numero(*mecha)),
+        ]),
+    }
+}
+
+/// Devuelve el modo de cadena representado por el objeto JSON.
+fn valor_a_modo_cadena(valor: &Valor) -> Result<ModoCadena, String> {
+    let donde = "modo_cadena";
+    match valor.campo("tipo") {
+        Some(Valor::Texto(tipo)) if tipo == "instantanea" => Ok(ModoCadena::Instantanea),
+        Some(Valor::Texto(tipo)) if tipo == "acortar_mecha" => {
+            match u32::try_from(campo_entero(valor, "mecha", donde)?) {
+                Ok(mecha) => Ok(ModoCadena::AcortarMecha(mecha)),
+                Err(_) => Err(format!("{}: mecha es demasiado grande", donde)),
+            }
+        }
+        _ => Err(format!(
+            "{}: tipo debe ser instantanea o acortar_mecha",
+            donde
+        )),
+    }
+}
+
+/// Devuelve todo el estado de la partida como objeto JSON: el mapa con el historial de impactos y las mechas,
+/// el tick, el modo de cadena, el jugador, sus bombas puestas y el estado del generador de azar.
+/// El estado del generador se escribe como texto hexadecimal porque los numeros JSON no representan todos los u64.
+pub fn partida_a_valor(partida: &Partida) -> Valor {
+    objeto(vec![
+        ("formato", texto(FORMATO_PARTIDA)),
+        ("version", numero(VERSION_PARTIDA as f64)),
+        ("tick", numero(partida.tick)),
+        ("modo_cadena", modo_cadena_a_valor(&partida.modo_cadena)),
+        (
+            "piedras_destructibles",
+            Valor::Booleano(partida.piedras_destructibles),
+        ),
+        ("azar", texto(&format!("{:016x}", partida.azar.estado))),
+        (
+            "jugador",
+            match &partida.jugador {
+                Some(jugador) => jugador_a_valor(jugador),
+                None => Valor::Nulo,
+            },
+        ),
+        (
+            "bombas_del_jugador",
+            Valor::Lista(
+                partida
+                    .bombas_del_jugador
+                    .iter()
+                    .map(coordenada_a_valor)
+                    .collect(),
+            ),
+        ),
+        ("mapa", mapa_a_valor(&partida.mapa)),
+    ])
+}
+
+/// Devuelve la partida en JSON, en una sola linea terminada en un salto de linea.
+pub fn partida_a_json(partida: &Partida) -> String {
+    format!("{}\n", partida_a_valor(partida))
+}
+
+/// Crea una partida a partir del objeto JSON que devuelve partida_a_valor.
+/// La partida cargada sigue exactamente igual que la original: los enemigos no vuelven a recibir daño de
+/// las bombas que ya los impactaron y los enemigos aleatorios repiten los mismos movimientos.
+pub fn valor_a_partida(valor: &Valor) -> Result<Partida, ParseError> {
+    let json = |mensaje: String| ParseError::Json { mensaje };
+    let donde = "partida";
+    match valor.campo("formato") {
+        Some(Valor::Texto(formato)) if formato == FORMATO_PARTIDA => {}
+        _ => {
+            return Err(json(format!(
+                "{}: formato debe ser {}",
+                donde, FORMATO_PARTIDA
+            )))
+        }
+    }
+    let version = campo_entero(valor, "version", donde).map_err(json)?;
+    if version != VERSION_PARTIDA {
+        return Err(json(format!("{}: version {} no soportada", donde, version)));
+    }
+
+    let mapa = match valor.campo("mapa") {
+        Some(mapa) => valor_a_mapa(mapa)?,
+        None => return Err(json(format!("{}: falta el campo mapa", donde))),
+    };
+    let tick = u32::try_from(campo_entero(valor, "tick", donde).map_err(json)?)
+        .map_err(|_| json(format!("{}: tick es demasiado grande", donde)))?;
+    let modo_cadena = match valor.campo("modo_cadena") {
+        Some(modo) => valor_a_modo_cadena(modo).map_err(json)?,
+        None => return Err(json(format!("{}: falta el campo modo_cadena", donde))),
+    };
+    let piedras_destructibles =
+        campo_booleano(valor, "piedras_destructibles", donde).map_err(json)?;
+    let azar = match valor.campo("azar") {
+        Some(Valor::Texto(estado)) => match u64::from_str_radix(estado, 16) {
+            Ok(estado) if estado != 0 => Azar { estado },
+            _ => return Err(json(format!("{}: azar invalido: {}", donde, estado))),
+        },
+        _ => return Err(json(format!("{}: azar debe ser un texto", donde))),
+    };
+
+    let fuera_de_rango =
+        |coordenada: &Coordenada| mapa.obtener_tile(coordenada.x, coordenada.y).is_none();
+    let jugador = match valor.campo("jugador") {
+        None | Some(Valor::Nulo) => None,
+        Some(jugador) => Some(valor_a_jugador(jugador).map_err(json)?),
+    };
+    if let Some(jugador) = &jugador {
+        if fuera_de_rango(&jugador.coordenadas()) {
+            return Err(json("jugador: fuera del mapa".to_string()));
+        }
+    }
+    let bombas_del_jugador = match valor.campo("bombas_del_jugador") {
+        Some(Valor::Lista(bombas)) => bombas
+            .iter()
+            .map(|bomba| valor_a_coordenada(bomba, "bombas_del_jugador"))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(json)?,
+        _ => {
+            return Err(json(format!(
+                "{}: bombas_del_jugador debe ser una lista",
+                donde
+            )))
+        }
+    };
+    if bombas_del_jugador.iter().any(fuera_de_rango) {
+        return Err(json("bombas_del_jugador: fuera del mapa".to_string()));
+    }
+
+    Ok(Partida {
+        mapa,
+        tick,
+        modo_cadena,
+        jugador,
+        bombas_del_jugador,
+        azar,
+        piedras_destructibles,
+    })
+}
+
+/// Transforma un texto JSON en una partida.
+pub fn partida_desde_json(texto: &str) -> Result<Partida, ParseError> {
+    let valor = parsear(texto).map_err(|error| ParseError::Json {
+        mensaje: error.to_string(),
+    })?;
+    valor_a_partida(&valor)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::turno;
+
+    #[test]
+    fn test_parsear_valores() {
+        assert_eq!(parsear("null"), Ok(Valor::Nulo));
+        assert_eq!(parsear(" true "), Ok(Valor::Booleano(true)));
+        assert_eq!(parsear("-1.5e2"), Ok(Valor::Numero(-150.0)));
+        assert_eq!(
+            parsear(r#""a\"b\\c\ná😀""#),
+            Ok(Valor::Texto("a\"b\\c\ná😀".to_string()))
+        );
+        assert_eq!(
+            parsear(r#"{"a": [1, {}, []], "b": false}"#),
+            Ok(Valor::Objeto(vec![
+                (
+                    "a".to_string(),
+                    Valor::Lista(vec![
+                        Valor::Numero(1.0),
+                        Valor::Objeto(vec![]),
+                        Valor::Lista(vec![])
+                    ])
+                ),
+                ("b".to_string(), Valor::Booleano(false)),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_parsear_errores() {
+        assert!(parsear("").is_err());
+        assert!(parsear("[1,]").is_err());
+        assert!(parsear("{\"a\" 1}").is_err());
+        assert!(parsear("\"sin cerrar").is_err());
+        assert!(parsear("1 2").is_err());
+        assert!(parsear("tru").is_err());
+        assert_eq!(parsear("[1, x]").unwrap_err().posicion, 4);
+        assert!(parsear(&"[".repeat(PROFUNDIDAD_MAXIMA + 1)).is_err());
+        assert!(parsear(&format!(
+            "{}{}",
+            "[".repeat(PROFUNDIDAD_MAXIMA),
+            "]".repeat(PROFUNDIDAD_MAXIMA)
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_escribir_valores() {
+        let valor = Valor::Objeto(vec![
+            ("n".to_string(), Valor::Numero(4294967295.0)),
+            ("t".to_string(), Valor::Texto("a\"\u{1}".to_string())),
+            (
+                "l".to_string(),
+                Valor::Lista(vec![Valor::Nulo, Valor::Booleano(true)]),
+            ),
+        ]);
+        let texto = valor.to_string();
+        assert_eq!(texto, r#"{"n":4294967295,"t":"a\"\u0001","l":[null,true]}"#);
+        assert_eq!(parsear(&texto), Ok(valor));
+    }
+
+    #[test]
+    fn test_mapa_a_json() {
+        let mapa: Mapa = "F2 B1 S3d2m4\nR W DL\n_ _ _".parse().unwrap();
+        assert_eq!(
+            mapa_a_json(&mapa),
+            concat!(
+                r#"{"ancho":3,"alto":3,"tiles":["#,
+                r#"[{"tipo":"enemigo","vida":2,"bombas_impactadas":[]},"#,
+                r#"{"tipo":"bomba","radio":1,"especial":false,"dmg":1,"mecha":null},"#,
+                r#"{"tipo":"bomba","radio":3,"especial":true,"dmg":2,"mecha":4}],"#,
+                r#"[{"tipo":"piedra"},{"tipo":"pared"},{"tipo":"desvio","direccion":"L"}],"#,
+                r#"[{"tipo":"vacio"},{"tipo":"vacio"},{"tipo":"vacio"}]]}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_ida_y_vuelta_con_impactos() {
+        let mut mapa: Mapa = "F3 B1\nB1 S1d2".parse().unwrap();
+        turno::jugar_turno(&mut mapa, 1, 0).unwrap();
+        let texto = mapa_a_json(&mapa);
+        assert!(texto.contains(r#""bombas_impactadas":[{"x":1,"y":0},{"x":0,"y":1}]"#));
+        assert_eq!(mapa_desde_json(&texto), Ok(mapa));
+
+        let mut mapa: Mapa = "F1a F2p F3e\nF4h F5v F6".parse().unwrap();
+        if let Some(Tile::Enemigo(enemigo)) = mapa.obtener_tile_mut(0, 1) {
+            enemigo.movimiento = Movimiento::Patrulla(Direccion::Izquierda);
+        }
+        let texto = mapa_a_json(&mapa);
+        assert!(texto.contains(
+            r#""vida":4,"bombas_impactadas":[],"movimiento":"patrulla","direccion":"L"}"#
+        ));
+        assert!(texto.contains(r#""vida":6,"bombas_impactadas":[]}"#));
+        assert_eq!(mapa_desde_json(&texto), Ok(mapa));
+
+        let mapa: Mapa = "Rb R Pr\nPs Rs _".parse().unwrap();
+        let texto = mapa_a_json(&mapa);
+        assert!(texto.contains(r#"[{"tipo":"piedra","premio":"bomba_extra"},{"tipo":"piedra"},{"tipo":"premio","premio":"mas_radio"}]"#));
+        assert_eq!(mapa_desde_json(&texto), Ok(mapa));
+        assert!(mapa_desde_json(r#"{"tiles":[[{"tipo":"premio"}]]}"#).is_err());
+
+        let mapa: Mapa = "J B1\n_ Jc2r3".parse().unwrap();
+        let texto = mapa_a_json(&mapa);
+        assert!(texto.contains(r#"{"tipo":"jugador","capacidad":2,"radio":3,"especial":false}"#));
+        assert_eq!(mapa_desde_json(&texto), Ok(mapa));
+        assert_eq!(
+            mapa_desde_json(r#"{"tiles":[[{"tipo":"jugador"}]]}"#),
+            Ok("J".parse().unwrap())
+        );
+
+        for path in ["mapas/mapa_3.txt", "mapas/mapa_4.txt", "mapas/mapa_5.txt"] {
+            let mapa = crate::bomberman_game::transformar_a_mapa(path).unwrap();
+            assert_eq!(mapa_desde_json(&mapa_a_json(&mapa)), Ok(mapa), "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_mapa_desde_json_errores() {
+        assert!(matches!(
+            mapa_desde_json("{\"tiles\":"),
+            Err(ParseError::Json { .. })
+        ));
+        assert_eq!(
+            mapa_desde_json(r#"{"tiles":[]}"#),
+            Err(ParseError::MapaVacio)
+        );
+        assert_eq!(
+            mapa_desde_json(r#"{"tiles":[[{"tipo":"vacio"}],[]]}"#),
+            Err(ParseError::FilaIrregular {
+                fila: 1,
+                esperadas: 1,
+                encontradas: 0
+            })
+        );
+        assert_eq!(
+            mapa_desde_json(r#"{"tiles":[[{"tipo":"vacio"},{"tipo":"enemigo","vida":0}]]}"#),
+            Err(ParseError::Json {
+                mensaje: "fila 0, columna 1: vida no puede ser 0".to_string()
+            })
+        );
+        assert!(mapa_desde_json(r#"{"tiles":[[{"tipo":"desvio","direccion":"X"}]]}"#).is_err());
+        assert!(mapa_desde_json(
+            r#"{"tiles":[[{"tipo":"enemigo","vida":1,"movimiento":"volar"}]]}"#
+        )
+        .is_err());
+        assert!(mapa_desde_json(
+            r#"{"tiles":[[{"tipo":"enemigo","vida":1,"movimiento":"patrulla"}]]}"#
+        )
+        .is_err());
+        assert!(mapa_desde_json(r#"{"ancho":2,"tiles":[[{"tipo":"vacio"}]]}"#).is_err());
+        assert!(mapa_desde_json(r#"{"tiles":[[{"tipo":"bomba","radio":-1}]]}"#).is_err());
+    }
+
+    #[test]
+    fn test_partida_ida_y_vuelta() {
+        let mapa = "Jr2 _ _ _\n_ _ B1m2 _\nS1 F3 _ F1a".parse().unwrap();
+        let mut partida = Partida::crear_con_semilla(mapa, ModoCadena::AcortarMecha(1), 7);
+        partida.piedras_destructibles = true;
+        partida.detonar(0, 2).unwrap();
+        partida.poner_bomba().unwrap();
+        partida.mover_jugador(Direccion::Derecha).unwrap();
+        partida.avanzar();
+
+        let texto = partida_a_json(&partida);
+        assert!(texto.starts_with(r#"{"formato":"partida","version":1,"tick":1,"#));
+        assert!(texto.contains(r#""modo_cadena":{"tipo":"acortar_mecha","mecha":1}"#));
+        assert!(texto.contains(r#""vida":2,"bombas_impactadas":[{"x":0,"y":2}]"#));
+        assert!(texto.contains(r#""bombas_del_jugador":[{"x":0,"y":0}]"#));
+        let mut cargada = partida_desde_json(&texto).unwrap();
+        assert_eq!(cargada, partida);
+
+        while !partida.terminada() && partida.tick < 10 {
+            assert_eq!(cargada.avanzar(), partida.avanzar());
+            assert_eq!(cargada, partida);
+        }
+    }
+
+    #[test]
+    fn test_partida_desde_json_errores() {
+        let partida = Partida::crear("J F1".parse().unwrap(), ModoCadena::Instantanea);
+        let texto = partida_a_json(&partida);
+        assert_eq!(partida_desde_json(&texto), Ok(partida));
+
+        let mapa = mapa_a_json(&"F1".parse().unwrap());
+        assert!(partida_desde_json(&mapa).is_err());
+        for (original, cambiado) in [
+            (r#""version":1"#, r#""version":2"#),
+            (r#""tick":0"#, r#""tick":-1"#),
+            (r#""tipo":"instantanea""#, r#""tipo":"lenta""#),
+            (r#""azar":"2545f4914f6cdd1d""#, r#""azar":"0""#),
+            (r#""azar":"2545f4914f6cdd1d""#, r#""azar":12"#),
+            (r#""x":0,"y":0,"capacidad""#, r#""x":2,"y":0,"capacidad""#),
+            (r#""vivo":true"#, r#""vivo":1"#),
+            (
+                r#""bombas_del_jugador":[]"#,
+                r#""bombas_del_jugador":[{"x":0,"y":3}]"#,
+            ),
+        ] {
+            assert!(texto.contains(original), "{}", original);
+            let invalido = texto.replace(original, cambiado);
+            assert!(
+                matches!(partida_desde_json(&invalido), Err(ParseError::Json { .. })),
+                "{}",
+                cambiado
+            );
+        }
+    }
+}
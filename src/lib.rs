@@ -1,3 +1,14 @@
+pub mod azar;
 pub mod bomberman_game;
+pub mod cli;
+pub mod cobertura;
+pub mod dibujo;
+pub mod grafo;
+pub mod ia;
+pub mod json;
 pub mod modelo;
+pub mod partida;
+pub mod solver;
+pub mod svg;
 pub mod turno;
+pub mod validador;
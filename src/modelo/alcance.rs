@@ -0,0 +1,10 @@
+use super::coordenada::Coordenada;
+
+/// Casilla alcanzada por una explosion.
+/// Guarda, en orden, los desvios que atraveso la explosion para llegar a la casilla.
+/// Si la explosion llego en linea recta, desvios esta vacio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alcance {
+    pub coordenada: Coordenada,
+    pub desvios: Vec<Coordenada>,
+}
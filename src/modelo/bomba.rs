@@ -1,14 +1,21 @@
 use super::coordenada::Coordenada;
 
+/// Daño que causa una bomba a cada enemigo que alcanza si el mapa no indica otro.
+pub const DMG_POR_DEFECTO: u32 = 1;
+
 /// Bomba que puede ser normal o especial.
 /// Si es especial, puede sortear piedras.
 /// Explota en un patron de cruz con cada lado siendo de largo radio.
+/// Cada enemigo alcanzado pierde dmg de vida.
+/// Si tiene mecha, explota sola cuando pasan esa cantidad de ticks de una Partida; si no, solo explota al detonarla.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Bomba {
     pub x: usize,
     pub y: usize,
     pub radio: u32,
     pub especial: bool,
+    pub dmg: u32,
+    pub mecha: Option<u32>,
 }
 
 impl Bomba {
@@ -16,12 +23,20 @@ impl Bomba {
     /// Si especial=true, la bomba va a ser capaz de sortear piedras, caso contrario no.
     /// Si radio=0, la bomba no va a causar daño a ninguna casilla.
     /// Si radio>0, la bomba va a causar daño a las casillas que se encuentren a distancia radio en linea recta.
+    /// La bomba causa DMG_POR_DEFECTO de daño y no tiene mecha.
     pub fn crear(x: usize, y: usize, radio: u32, especial: bool) -> Bomba {
+        Bomba::crear_con_dmg(x, y, radio, especial, DMG_POR_DEFECTO)
+    }
+
+    /// Crea una bomba que le descuenta dmg de vida a cada enemigo que alcanza.
+    pub fn crear_con_dmg(x: usize, y: usize, radio: u32, especial: bool, dmg: u32) -> Bomba {
         Bomba {
             x,
             y,
             radio,
             especial,
+            dmg,
+            mecha: None,
         }
     }
 
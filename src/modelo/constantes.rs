@@ -5,3 +5,22 @@ pub const PARED: char = 'W';
 pub const PIEDRA: char = 'R';
 pub const DESVIO: char = 'D';
 pub const VACIO: char = '_';
+pub const JUGADOR: char = 'J';
+pub const MODIFICADOR_DMG: char = 'd';
+pub const MODIFICADOR_MECHA: char = 'm';
+pub const MODIFICADOR_CAPACIDAD: char = 'c';
+pub const MODIFICADOR_RADIO: char = 'r';
+pub const MODIFICADOR_ESPECIAL: char = 's';
+pub const MODIFICADOR_IMPACTO: char = 'i';
+pub const SEPARADOR_COORDENADAS: char = ',';
+pub const MOVIMIENTO_ALEATORIO: char = 'a';
+pub const MOVIMIENTO_PATRULLA_HORIZONTAL: char = 'h';
+pub const MOVIMIENTO_PATRULLA_VERTICAL: char = 'v';
+pub const MOVIMIENTO_PATRULLA_IZQUIERDA: char = 'H';
+pub const MOVIMIENTO_PATRULLA_ARRIBA: char = 'V';
+pub const MOVIMIENTO_PERSEGUIDOR: char = 'p';
+pub const MOVIMIENTO_HUIDIZO: char = 'e';
+pub const PREMIO: char = 'P';
+pub const PREMIO_BOMBA_EXTRA: char = 'b';
+pub const PREMIO_MAS_RADIO: char = 'r';
+pub const PREMIO_BOMBA_ESPECIAL: char = 's';
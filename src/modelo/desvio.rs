@@ -12,11 +12,6 @@ impl Desvio {
     /// Devuelve el char correspondiente para simbolizar la direccion del desvio.
     /// U: Arriba, D: Abajo, L: Izquierda, R: Derecha.
     pub fn char_direccion(&self) -> char {
-        match self.direccion {
-            Direccion::Arriba => 'U',
-            Direccion::Abajo => 'D',
-            Direccion::Izquierda => 'L',
-            Direccion::Derecha => 'R',
-        }
+        self.direccion.a_char()
     }
 }
@@ -7,3 +7,56 @@ pub enum Direccion {
     Izquierda,
     Derecha,
 }
+
+impl Direccion {
+    /// Las cuatro direcciones, en el orden en el que se prueban cuando hay que elegir entre ellas.
+    pub const TODAS: [Direccion; 4] = [
+        Direccion::Arriba,
+        Direccion::Abajo,
+        Direccion::Izquierda,
+        Direccion::Derecha,
+    ];
+
+    /// Devuelve la direccion que corresponde al caracter usado en los mapas.
+    /// U: Arriba, D: Abajo, L: Izquierda, R: Derecha. Cualquier otro caracter devuelve None.
+    pub fn desde_char(c: char) -> Option<Direccion> {
+        match c {
+            'U' => Some(Direccion::Arriba),
+            'D' => Some(Direccion::Abajo),
+            'L' => Some(Direccion::Izquierda),
+            'R' => Some(Direccion::Derecha),
+            _ => None,
+        }
+    }
+
+    /// Devuelve el caracter con el que se escribe la direccion en los mapas. Es la inversa de desde_char.
+    pub fn a_char(&self) -> char {
+        match self {
+            Direccion::Arriba => 'U',
+            Direccion::Abajo => 'D',
+            Direccion::Izquierda => 'L',
+            Direccion::Derecha => 'R',
+        }
+    }
+
+    /// Devuelve el vector (dx, dy) que representa un paso en esta direccion.
+    /// El eje y crece hacia abajo, igual que en las coordenadas del mapa.
+    pub fn desplazamiento(&self) -> (i32, i32) {
+        match self {
+            Direccion::Arriba => (0, -1),
+            Direccion::Abajo => (0, 1),
+            Direccion::Izquierda => (-1, 0),
+            Direccion::Derecha => (1, 0),
+        }
+    }
+
+    /// Devuelve la direccion contraria.
+    pub fn opuesta(&self) -> Direccion {
+        match self {
+            Direccion::Arriba => Direccion::Abajo,
+            Direccion::Abajo => Direccion::Arriba,
+            Direccion::Izquierda => Direccion::Derecha,
+            Direccion::Derecha => Direccion::Izquierda,
+        }
+    }
+}
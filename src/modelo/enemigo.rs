@@ -1,25 +1,28 @@
-use super::coordenada::Coordenada;
+use super::{coordenada::Coordenada, movimiento::Movimiento};
 use std::collections::HashSet;
 
 /// Enemigo que tiene vida y puede ser afectado por las bombas.
 /// Ser alcanzado por una explosion no significa que vaya a ser destruido.
 /// Si la vida es menor o igual al daño de la bomba, es destruido
+/// Durante una Partida se mueve segun su movimiento.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Enemigo {
     pub x: usize,
     pub y: usize,
     pub vida: u32,
     pub bombas_impactadas: HashSet<Coordenada>,
+    pub movimiento: Movimiento,
 }
 
 impl Enemigo {
-    /// Crea un enemigo.
+    /// Crea un enemigo quieto.
     pub fn crear(x: usize, y: usize, vida: u32) -> Enemigo {
         Enemigo {
             x,
             y,
             vida,
             bombas_impactadas: std::collections::HashSet::new(),
+            movimiento: Movimiento::Quieto,
         }
     }
 
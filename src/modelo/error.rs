@@ -0,0 +1,134 @@
+use std::fmt;
+
+/// Tipo de falla encontrada al parsear un token del mapa.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TipoError {
+    /// El enemigo no tiene vida. Ejemplo: F.
+    EnemigoSinVida,
+    /// El enemigo tiene vida 0. Ejemplo: F0.
+    VidaCero,
+    /// La bomba no tiene radio. Ejemplo: B.
+    BombaSinRadio,
+    /// La bomba tiene radio 0. Ejemplo: B0.
+    RadioCero,
+    /// La bomba tiene daño 0. Ejemplo: B2d0.
+    DmgCero,
+    /// La bomba tiene mecha 0. Ejemplo: B2m0.
+    MechaCero,
+    /// El premio no es b, r ni s. Ejemplo: Rx, P.
+    PremioInvalido,
+    /// El movimiento del enemigo no es a, h, H, v, V, p ni e. Ejemplo: F1z.
+    MovimientoInvalido,
+    /// El jugador puede tener 0 bombas a la vez. Ejemplo: Jc0.
+    CapacidadCero,
+    /// El modificador s del jugador no es 0 ni 1. Ejemplo: Js2.
+    EspecialInvalido,
+    /// El modificador no existe. Ejemplo: B2x1, Jx1.
+    ModificadorDesconocido,
+    /// El modificador no tiene valor. Ejemplo: B2d, Jc.
+    ModificadorSinValor,
+    /// El mismo modificador aparece mas de una vez. Ejemplo: B3d2d5, Jc1c2.
+    ModificadorRepetido,
+    /// La bomba que impacto al enemigo no es i<x>,<y>. Ejemplo: F2i1, F2i,3.
+    ImpactoInvalido,
+    /// El sufijo numerico tiene caracteres que no son digitos. Ejemplo: B1x.
+    NumeroInvalido,
+    /// El sufijo numerico no entra en un u32. Ejemplo: F4294967296.
+    NumeroFueraDeRango,
+    /// El desvio no tiene direccion. Ejemplo: D.
+    DesvioSinDireccion,
+    /// La direccion del desvio no es U, D, L ni R. Ejemplo: DX.
+    DireccionInvalida,
+    /// El primer caracter no corresponde a ninguna pieza conocida.
+    PiezaDesconocida,
+}
+
+impl fmt::Display for TipoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let descripcion = match self {
+            TipoError::EnemigoSinVida => "los enemigos deben tener vida",
+            TipoError::VidaCero => "un enemigo no puede tener vida 0",
+            TipoError::BombaSinRadio => "una bomba debe tener radio",
+            TipoError::RadioCero => "una bomba no puede tener radio 0",
+            TipoError::DmgCero => "una bomba no puede tener daño 0",
+            TipoError::MechaCero => "una bomba no puede tener mecha 0",
+            TipoError::PremioInvalido => "premio invalido, debe ser b, r o s",
+            TipoError::MovimientoInvalido => "movimiento invalido, debe ser a, h, H, v, V, p o e",
+            TipoError::CapacidadCero => "un jugador no puede tener capacidad 0",
+            TipoError::EspecialInvalido => "el modificador s del jugador debe ser 0 o 1",
+            TipoError::ModificadorDesconocido => "modificador desconocido",
+            TipoError::ModificadorSinValor => "el modificador debe tener valor",
+            TipoError::ModificadorRepetido => "el modificador esta repetido",
+            TipoError::ImpactoInvalido => "impacto invalido, debe ser i<x>,<y>",
+            TipoError::NumeroInvalido => "el numero tiene caracteres invalidos",
+            TipoError::NumeroFueraDeRango => "el numero es demasiado grande",
+            TipoError::DesvioSinDireccion => "un desvio debe tener direccion",
+            TipoError::DireccionInvalida => "direccion de desvio invalida",
+            TipoError::PiezaDesconocida => "pieza desconocida",
+        };
+        write!(f, "{}", descripcion)
+    }
+}
+
+/// Error producido al transformar un texto en un mapa.
+/// Las filas y columnas empiezan en 0, igual que las coordenadas del mapa.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// Un token del mapa no tiene el formato correcto.
+    Token {
+        fila: usize,
+        columna: usize,
+        token: String,
+        tipo: TipoError,
+    },
+    /// Una fila tiene una cantidad de columnas distinta a la primera.
+    FilaIrregular {
+        fila: usize,
+        esperadas: usize,
+        encontradas: usize,
+    },
+    /// El mapa no tiene filas o sus filas no tienen columnas.
+    MapaVacio,
+    /// No se pudo abrir el archivo.
+    Archivo { path: String, mensaje: String },
+    /// No se pudo leer una linea del archivo.
+    Lectura { fila: usize, mensaje: String },
+    /// El mapa en formato JSON no es valido.
+    Json { mensaje: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Token {
+                fila,
+                columna,
+                token,
+                tipo,
+            } => write!(
+                f,
+                "fila {}, columna {}: >{}<: {}",
+                fila, columna, token, tipo
+            ),
+            ParseError::FilaIrregular {
+                fila,
+                esperadas,
+                encontradas,
+            } => write!(
+                f,
+                "fila {}: tiene {} columnas, se esperaban {}",
+                fila, encontradas, esperadas
+            ),
+            ParseError::MapaVacio => write!(f, "el mapa esta vacio"),
+            ParseError::Archivo { path, mensaje } => {
+                write!(f, "no se pudo abrir el archivo {}: {}", path, mensaje)
+            }
+            ParseError::Lectura { fila, mensaje } => {
+                write!(f, "fila {}: no se pudo leer la linea: {}", fila, mensaje)
+            }
+            ParseError::Json { mensaje } => write!(f, "JSON invalido: {}", mensaje),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
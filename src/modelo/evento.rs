@@ -0,0 +1,58 @@
+use super::{coordenada::Coordenada, premio::Premio};
+
+/// Evento ocurrido durante la detonacion de una bomba.
+/// Los eventos se registran en el orden en el que ocurren, por lo que sirven para animar o depurar una explosion.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Evento {
+    /// Una bomba exploto. Si fue detonada por otra bomba, disparada_por tiene la posicion de esa bomba.
+    BombaDetonada {
+        bomba: Coordenada,
+        disparada_por: Option<Coordenada>,
+    },
+    /// La explosion de una bomba alcanzo una casilla, atravesando en orden los desvios indicados.
+    TileAlcanzado {
+        bomba: Coordenada,
+        tile: Coordenada,
+        desvios: Vec<Coordenada>,
+    },
+    /// Un enemigo recibio daño y sobrevivio.
+    EnemigoDaniado {
+        bomba: Coordenada,
+        enemigo: Coordenada,
+        vida_restante: u32,
+    },
+    /// Un enemigo recibio daño y fue destruido.
+    EnemigoEliminado {
+        bomba: Coordenada,
+        enemigo: Coordenada,
+    },
+    /// La explosion de una bomba alcanzo a otra bomba y la hizo detonar.
+    ReaccionEnCadena {
+        origen: Coordenada,
+        bomba: Coordenada,
+    },
+    /// La explosion de una bomba alcanzo a otra bomba y, en lugar de hacerla detonar, le acorto la mecha.
+    MechaAcortada {
+        origen: Coordenada,
+        bomba: Coordenada,
+        mecha: u32,
+    },
+    /// La explosion de una bomba alcanzo al jugador de una partida.
+    JugadorAlcanzado {
+        bomba: Coordenada,
+        jugador: Coordenada,
+    },
+    /// Un enemigo se movio una casilla durante un tick de una partida.
+    EnemigoMovido {
+        origen: Coordenada,
+        destino: Coordenada,
+    },
+    /// Un enemigo llego a la casilla del jugador y lo elimino.
+    JugadorAtrapado { enemigo: Coordenada },
+    /// La explosion de una bomba destruyo una piedra. Si escondia un premio, quedo a la vista en su casilla.
+    PiedraDestruida {
+        bomba: Coordenada,
+        piedra: Coordenada,
+        premio: Option<Premio>,
+    },
+}
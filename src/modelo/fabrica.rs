@@ -1,136 +1,296 @@
 use crate::modelo::tile::Tile;
 
 use super::{
-    bomba::Bomba,
-    constantes::{BOMBA_ESPECIAL, BOMBA_NORMAL, DESVIO, ENEMIGO, PARED, PIEDRA, VACIO},
+    bomba::{Bomba, DMG_POR_DEFECTO},
+    constantes::{
+        BOMBA_ESPECIAL, BOMBA_NORMAL, DESVIO, ENEMIGO, JUGADOR, MODIFICADOR_CAPACIDAD,
+        MODIFICADOR_DMG, MODIFICADOR_ESPECIAL, MODIFICADOR_IMPACTO, MODIFICADOR_MECHA,
+        MODIFICADOR_RADIO, PARED, PIEDRA, PREMIO, SEPARADOR_COORDENADAS, VACIO,
+    },
+    coordenada::Coordenada,
     desvio::Desvio,
     direccion::Direccion,
     enemigo::Enemigo,
+    error::{ParseError, TipoError},
+    jugador::Jugador,
+    movimiento::Movimiento,
     obstaculo::Obstaculo,
+    premio::Premio,
 };
 
+/// Parsea el sufijo numerico de un token, por ejemplo el "12" de F12.
+/// Devuelve Ok(None) si el sufijo esta vacio.
+/// Devuelve un error si el sufijo tiene caracteres que no son digitos o si el numero no entra en un u32.
+fn parsear_numero(sufijo: &str) -> Result<Option<u32>, TipoError> {
+    if sufijo.is_empty() {
+        return Ok(None);
+    }
+    let mut numero: u32 = 0;
+    for c in sufijo.chars() {
+        let digito = match c.to_digit(10) {
+            Some(digito) => digito,
+            None => return Err(TipoError::NumeroInvalido),
+        };
+        numero = match numero.checked_mul(10).and_then(|n| n.checked_add(digito)) {
+            Some(numero) => numero,
+            None => return Err(TipoError::NumeroFueraDeRango),
+        };
+    }
+    Ok(Some(numero))
+}
+
+/// Separa un texto en el numero con el que empieza y lo que le sigue.
+/// Ejemplo: "3d2" -> ("3", "d2").
+fn separar_numero(s: &str) -> (&str, &str) {
+    let fin = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(fin)
+}
+
+/// Parsea los modificadores que siguen al radio de una bomba o al simbolo del jugador.
+/// Cada modificador es una letra seguida de un numero. Ejemplo: d2.
+/// Cada letra puede aparecer una sola vez: B3d2d5 es un error.
+fn parsear_modificadores(s: &str) -> Result<Vec<(char, u32)>, TipoError> {
+    let mut modificadores: Vec<(char, u32)> = Vec::new();
+    let mut resto = s;
+    while let Some(letra) = resto.chars().next() {
+        let (numero, siguiente) = separar_numero(&resto[letra.len_utf8()..]);
+        let valor = match parsear_numero(numero)? {
+            Some(valor) => valor,
+            None => return Err(TipoError::ModificadorSinValor),
+        };
+        if modificadores.iter().any(|(repetida, _)| *repetida == letra) {
+            return Err(TipoError::ModificadorRepetido);
+        }
+        modificadores.push((letra, valor));
+        resto = siguiente;
+    }
+    Ok(modificadores)
+}
+
+/// Parsea las bombas que ya impactaron a un enemigo. Ejemplo: el i1,0i0,2 de F2i1,0i0,2.
+/// Cada impacto es la letra i seguida de las coordenadas x,y de la bomba.
+fn parsear_impactos(s: &str) -> Result<Vec<Coordenada>, TipoError> {
+    let mut impactos = Vec::new();
+    let mut resto = s;
+    while let Some(letra) = resto.chars().next() {
+        if letra != MODIFICADOR_IMPACTO {
+            return Err(TipoError::ImpactoInvalido);
+        }
+        let (x, siguiente) = separar_numero(&resto[letra.len_utf8()..]);
+        let siguiente = match siguiente.strip_prefix(SEPARADOR_COORDENADAS) {
+            Some(siguiente) => siguiente,
+            None => return Err(TipoError::ImpactoInvalido),
+        };
+        let (y, siguiente) = separar_numero(siguiente);
+        match (parsear_numero(x)?, parsear_numero(y)?) {
+            (Some(x), Some(y)) => impactos.push(Coordenada {
+                x: x as usize,
+                y: y as usize,
+            }),
+            _ => return Err(TipoError::ImpactoInvalido),
+        }
+        resto = siguiente;
+    }
+    Ok(impactos)
+}
+
 /// Crea un enemigo a partir de un string.
-/// El string debe tener el siguiente formato: F(numero). Ejemplo: F2.
-/// Si el string no tiene el formato correcto, devuelve un error.
-fn crear_enemigo(
-    x_pos: usize,
-    y_pos: usize,
-    segundo_caracter: Option<char>,
-) -> Result<Tile, String> {
-    let vida = match segundo_caracter {
-        Some(c) => c.to_digit(10),
-        None => None,
+/// El string debe tener el siguiente formato: F(numero)[movimiento][i(x),(y)]... Ejemplo: F2, F12, F3p, F1H, F2i1,0.
+/// El sufijo opcional indica como se mueve durante una partida; si no esta, el enemigo queda quieto.
+/// Cada i(x),(y) indica una bomba que ya lo impacto y no vuelve a dañarlo.
+/// Si el string no tiene el formato correcto, devuelve el tipo de error.
+fn crear_enemigo(x_pos: usize, y_pos: usize, s: &str) -> Result<Tile, TipoError> {
+    let (vida, sufijo) = separar_numero(&s[1..]);
+    let (movimiento, impactos) = match sufijo.chars().next() {
+        Some(c) if c != MODIFICADOR_IMPACTO => (
+            Movimiento::desde_char(c).ok_or(TipoError::MovimientoInvalido)?,
+            &sufijo[c.len_utf8()..],
+        ),
+        _ => (Movimiento::Quieto, sufijo),
     };
-    match vida {
+    let impactos = parsear_impactos(impactos)?;
+    match parsear_numero(vida)? {
+        Some(0) => Err(TipoError::VidaCero),
         Some(vida) => {
-            if vida > 0 {
-                Ok(Tile::Enemigo(Enemigo::crear(x_pos, y_pos, vida)))
-            } else {
-                Err(format!(
-                    "Un enemigo no puede tener vida negativa: F{}",
-                    vida
-                ))
+            let mut enemigo = Enemigo::crear(x_pos, y_pos, vida);
+            enemigo.movimiento = movimiento;
+            for bomba in impactos {
+                enemigo.recibir_impacto(bomba.x, bomba.y);
             }
+            Ok(Tile::Enemigo(enemigo))
         }
-        None => Err("Los enemigos deben tener vida".to_string()),
+        None => Err(TipoError::EnemigoSinVida),
     }
 }
 
 /// Crea una bomba a partir de un string.
-/// El string debe tener el siguiente formato: B(numero). Ejemplo: B2.
-/// Si el string no tiene el formato correcto, devuelve un error.
-fn crear_bomba(
-    x_pos: usize,
-    y_pos: usize,
-    especial: bool,
-    segundo_caracter: Option<char>,
-) -> Result<Tile, String> {
-    let radio = match segundo_caracter {
-        Some(c) => c.to_digit(10),
-        None => None,
+/// El string debe tener el siguiente formato: B(numero)[d(numero)][m(numero)]. Ejemplo: B2, B15, B3d2, B2m3.
+/// El modificador opcional d indica el daño que causa a cada enemigo; si no esta, es DMG_POR_DEFECTO.
+/// El modificador opcional m indica la mecha: cuantos ticks faltan para que explote sola.
+/// Si el string no tiene el formato correcto, devuelve el tipo de error.
+fn crear_bomba(x_pos: usize, y_pos: usize, especial: bool, s: &str) -> Result<Tile, TipoError> {
+    let (radio, modificadores) = separar_numero(&s[1..]);
+    let radio = match parsear_numero(radio)? {
+        Some(0) => return Err(TipoError::RadioCero),
+        Some(radio) => radio,
+        None => return Err(TipoError::BombaSinRadio),
     };
-    match radio {
-        Some(radio) => {
-            if radio > 0 {
-                let bomba = Bomba::crear(x_pos, y_pos, radio, especial);
-                if especial {
-                    Ok(Tile::BombaEspecial(bomba))
-                } else {
-                    Ok(Tile::BombaNormal(bomba))
-                }
-            } else {
-                Err(format!(
-                    "Una bomba no puede tener radio negativo: B{}",
-                    radio
-                ))
-            }
+    let mut dmg = DMG_POR_DEFECTO;
+    let mut mecha = None;
+    for (letra, valor) in parsear_modificadores(modificadores)? {
+        match (letra, valor) {
+            (MODIFICADOR_DMG, 0) => return Err(TipoError::DmgCero),
+            (MODIFICADOR_DMG, valor) => dmg = valor,
+            (MODIFICADOR_MECHA, 0) => return Err(TipoError::MechaCero),
+            (MODIFICADOR_MECHA, valor) => mecha = Some(valor),
+            _ => return Err(TipoError::ModificadorDesconocido),
+        }
+    }
+    let mut bomba = Bomba::crear_con_dmg(x_pos, y_pos, radio, especial, dmg);
+    bomba.mecha = mecha;
+    if especial {
+        Ok(Tile::BombaEspecial(bomba))
+    } else {
+        Ok(Tile::BombaNormal(bomba))
+    }
+}
+
+/// Crea un jugador a partir de un string.
+/// El string debe tener el siguiente formato: J[c(numero)][r(numero)][s(0|1)]. Ejemplo: J, Jc2, Jc2r3, Js1.
+/// El modificador opcional c indica cuantas bombas puede tener puestas a la vez y r el radio de sus bombas.
+/// Si no estan, son CAPACIDAD_POR_DEFECTO y RADIO_POR_DEFECTO.
+/// El modificador opcional s1 indica que pone bombas especiales; si no esta, pone bombas normales.
+/// Si el string no tiene el formato correcto, devuelve el tipo de error.
+fn crear_jugador(x_pos: usize, y_pos: usize, s: &str) -> Result<Tile, TipoError> {
+    let mut jugador = Jugador::crear(x_pos, y_pos);
+    for (letra, valor) in parsear_modificadores(&s[1..])? {
+        match (letra, valor) {
+            (MODIFICADOR_CAPACIDAD, 0) => return Err(TipoError::CapacidadCero),
+            (MODIFICADOR_CAPACIDAD, valor) => jugador.capacidad = valor,
+            (MODIFICADOR_RADIO, 0) => return Err(TipoError::RadioCero),
+            (MODIFICADOR_RADIO, valor) => jugador.radio = valor,
+            (MODIFICADOR_ESPECIAL, 0) => jugador.especial = false,
+            (MODIFICADOR_ESPECIAL, 1) => jugador.especial = true,
+            (MODIFICADOR_ESPECIAL, _) => return Err(TipoError::EspecialInvalido),
+            _ => return Err(TipoError::ModificadorDesconocido),
         }
-        None => Err("Una bomba debe tener radio, B".to_string()),
+    }
+    Ok(Tile::Jugador(jugador))
+}
+
+/// Parsea el premio que sigue al simbolo de una piedra o de un premio. Ejemplo: la b de Rb.
+/// Devuelve Ok(None) si no hay premio.
+fn parsear_premio(sufijo: &str) -> Result<Option<Premio>, TipoError> {
+    let mut caracteres = sufijo.chars();
+    match (caracteres.next(), caracteres.next()) {
+        (None, _) => Ok(None),
+        (Some(c), None) => Premio::desde_char(c)
+            .map(Some)
+            .ok_or(TipoError::PremioInvalido),
+        _ => Err(TipoError::PremioInvalido),
+    }
+}
+
+/// Crea una piedra a partir de un string.
+/// El string debe tener el siguiente formato: R[premio]. Ejemplo: R, Rb, Rr, Rs.
+/// Si el string no tiene el formato correcto, devuelve el tipo de error.
+fn crear_piedra(x_pos: usize, y_pos: usize, s: &str) -> Result<Tile, TipoError> {
+    let mut piedra = Obstaculo::crear(x_pos, y_pos, false);
+    piedra.premio = parsear_premio(&s[1..])?;
+    Ok(Tile::Piedra(piedra))
+}
+
+/// Crea un premio a la vista a partir de un string.
+/// El string debe tener el siguiente formato: P(premio). Ejemplo: Pb.
+/// Si el string no tiene el formato correcto, devuelve el tipo de error.
+fn crear_premio(s: &str) -> Result<Tile, TipoError> {
+    match parsear_premio(&s[1..])? {
+        Some(premio) => Ok(Tile::Premio(premio)),
+        None => Err(TipoError::PremioInvalido),
     }
 }
 
 /// Crea un desvio a partir de un string.
 /// El string debe tener el siguiente formato: D(direccion). Ejemplo: DU.
-/// Si el string no tiene el formato correcto, devuelve un error.
+/// Si el string no tiene el formato correcto, devuelve el tipo de error.
 fn crear_desvio(
     x_pos: usize,
     y_pos: usize,
     segundo_caracter: Option<char>,
-) -> Result<Tile, String> {
+) -> Result<Tile, TipoError> {
     match segundo_caracter {
-        None => Err("Un desvio debe tener direccion, D".to_string()),
-        Some(direccion) => {
-            let direccion = match direccion {
-                'U' => Direccion::Arriba,
-                'D' => Direccion::Abajo,
-                'L' => Direccion::Izquierda,
-                'R' => Direccion::Derecha,
-                _ => return Err(format!("Direccion invalida: D{}", direccion)),
-            };
-            Ok(Tile::Desvio(Desvio {
+        None => Err(TipoError::DesvioSinDireccion),
+        Some(direccion) => match Direccion::desde_char(direccion) {
+            Some(direccion) => Ok(Tile::Desvio(Desvio {
                 x: x_pos,
                 y: y_pos,
                 direccion,
-            }))
-        }
+            })),
+            None => Err(TipoError::DireccionInvalida),
+        },
     }
 }
 
 /// Crea una pieza a partir de un string.
-/// El string debe tener el siguiente formato: <tipo><numero>. Ejemplo: F2, B2, S2, R, W, D2, _.
-/// Si el string no tiene el formato correcto, devuelve un error.
-pub fn crear_pieza(s: &str, x_pos: usize, y_pos: usize) -> Result<Tile, String> {
+/// El string debe tener el siguiente formato: <tipo><numero>. Ejemplo: F2, B2, S2, R, Rb, W, D2, J, Pr, _.
+/// Si el string no tiene el formato correcto, devuelve un error con la fila, la columna, el token y el tipo de falla.
+pub fn crear_pieza(s: &str, x_pos: usize, y_pos: usize) -> Result<Tile, ParseError> {
     let primer_caracter = s.chars().next();
     let segundo_caracter = s.chars().nth(1);
-    match primer_caracter {
-        Some(ENEMIGO) => crear_enemigo(x_pos, y_pos, segundo_caracter),
-        Some(BOMBA_NORMAL) => crear_bomba(x_pos, y_pos, false, segundo_caracter),
-        Some(BOMBA_ESPECIAL) => crear_bomba(x_pos, y_pos, true, segundo_caracter),
-        Some(PIEDRA) => Ok(Tile::Piedra(Obstaculo::crear(x_pos, y_pos, false))),
+    let pieza = match primer_caracter {
+        Some(ENEMIGO) => crear_enemigo(x_pos, y_pos, s),
+        Some(BOMBA_NORMAL) => crear_bomba(x_pos, y_pos, false, s),
+        Some(BOMBA_ESPECIAL) => crear_bomba(x_pos, y_pos, true, s),
+        Some(PIEDRA) => crear_piedra(x_pos, y_pos, s),
         Some(PARED) => Ok(Tile::Pared(Obstaculo::crear(x_pos, y_pos, true))),
         Some(DESVIO) => crear_desvio(x_pos, y_pos, segundo_caracter),
+        Some(JUGADOR) => crear_jugador(x_pos, y_pos, s),
+        Some(PREMIO) => crear_premio(s),
         Some(VACIO) => Ok(Tile::Vacio),
-        _ => Err(format!("No se pudo crear la pieza: >{}<", s)),
-    }
+        _ => Err(TipoError::PiezaDesconocida),
+    };
+    pieza.map_err(|tipo| ParseError::Token {
+        fila: y_pos,
+        columna: x_pos,
+        token: s.to_string(),
+        tipo,
+    })
 }
 
 #[cfg(test)]
 mod test {
     use crate::modelo::desvio::Desvio;
     use crate::modelo::direccion::Direccion;
+    use crate::modelo::error::{ParseError, TipoError};
     use crate::modelo::tile::Tile;
     use crate::modelo::{bomba::Bomba, enemigo::Enemigo, obstaculo::Obstaculo};
 
     #[test]
     fn test_crear_pieza() {
         let tile = super::crear_pieza("F", 0, 0);
-        assert_eq!(tile, Err("Los enemigos deben tener vida".to_string()));
+        assert_eq!(
+            tile,
+            Err(ParseError::Token {
+                fila: 0,
+                columna: 0,
+                token: "F".to_string(),
+                tipo: TipoError::EnemigoSinVida
+            })
+        );
 
         let tile = super::crear_pieza("F2", 0, 0);
         assert_eq!(tile, Ok(Tile::Enemigo(Enemigo::crear(0, 0, 2))));
 
         let tile = super::crear_pieza("B", 0, 0);
-        assert_eq!(tile, Err("Una bomba debe tener radio, B".to_string()));
+        assert_eq!(
+            tile,
+            Err(ParseError::Token {
+                fila: 0,
+                columna: 0,
+                token: "B".to_string(),
+                tipo: TipoError::BombaSinRadio
+            })
+        );
 
         let tile = super::crear_pieza("B2", 0, 0);
         assert_eq!(tile, Ok(Tile::BombaNormal(Bomba::crear(0, 0, 2, false))));
@@ -145,7 +305,7 @@ mod test {
         assert_eq!(tile, Ok(Tile::Pared(Obstaculo::crear(0, 0, true))));
 
         let tile = super::crear_pieza("D", 0, 0);
-        assert_eq!(tile.is_err(), true);
+        assert!(tile.is_err());
 
         let tile = super::crear_pieza("DU", 0, 0);
         assert_eq!(
@@ -160,4 +320,342 @@ mod test {
         let tile = super::crear_pieza("_", 0, 0);
         assert_eq!(tile, Ok(Tile::Vacio));
     }
+
+    #[test]
+    fn test_crear_pieza_numeros_de_varios_digitos() {
+        let tile = super::crear_pieza("F12", 0, 0);
+        assert_eq!(tile, Ok(Tile::Enemigo(Enemigo::crear(0, 0, 12))));
+
+        let tile = super::crear_pieza("B15", 0, 0);
+        assert_eq!(tile, Ok(Tile::BombaNormal(Bomba::crear(0, 0, 15, false))));
+
+        let tile = super::crear_pieza("S10", 0, 0);
+        assert_eq!(tile, Ok(Tile::BombaEspecial(Bomba::crear(0, 0, 10, true))));
+
+        let tile = super::crear_pieza("F00", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::VidaCero,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("F1x", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::MovimientoInvalido,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("F4294967296", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::NumeroFueraDeRango,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("B4294967295", 0, 0);
+        assert_eq!(
+            tile,
+            Ok(Tile::BombaNormal(Bomba::crear(0, 0, u32::MAX, false)))
+        );
+    }
+
+    #[test]
+    fn test_crear_pieza_error_con_posicion() {
+        let tile = super::crear_pieza("X3", 4, 2);
+        assert_eq!(
+            tile,
+            Err(ParseError::Token {
+                fila: 2,
+                columna: 4,
+                token: "X3".to_string(),
+                tipo: TipoError::PiezaDesconocida
+            })
+        );
+
+        let tile = super::crear_pieza("DX", 1, 5);
+        assert_eq!(
+            tile,
+            Err(ParseError::Token {
+                fila: 5,
+                columna: 1,
+                token: "DX".to_string(),
+                tipo: TipoError::DireccionInvalida
+            })
+        );
+    }
+
+    #[test]
+    fn test_crear_bomba_con_dmg() {
+        let tile = super::crear_pieza("B3d2", 0, 0);
+        assert_eq!(
+            tile,
+            Ok(Tile::BombaNormal(Bomba::crear_con_dmg(0, 0, 3, false, 2)))
+        );
+
+        let tile = super::crear_pieza("S12d10", 0, 0);
+        assert_eq!(
+            tile,
+            Ok(Tile::BombaEspecial(Bomba::crear_con_dmg(
+                0, 0, 12, true, 10
+            )))
+        );
+
+        let tile = super::crear_pieza("B3d0", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::DmgCero,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("B3d", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::ModificadorSinValor,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("B1x2", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::ModificadorDesconocido,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("B2d3m4", 0, 0);
+        let mut bomba = Bomba::crear_con_dmg(0, 0, 2, false, 3);
+        bomba.mecha = Some(4);
+        assert_eq!(tile, Ok(Tile::BombaNormal(bomba)));
+
+        let tile = super::crear_pieza("S1m2", 0, 0);
+        assert!(matches!(
+            tile,
+            Ok(Tile::BombaEspecial(Bomba { mecha: Some(2), .. }))
+        ));
+
+        let tile = super::crear_pieza("B3m0", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::MechaCero,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("Bd2", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::BombaSinRadio,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_modificador_repetido() {
+        for token in ["B3d2d5", "S1m2d1m3", "Jc1c2", "Jr2c1r2"] {
+            assert!(
+                matches!(
+                    super::crear_pieza(token, 0, 0),
+                    Err(ParseError::Token {
+                        tipo: TipoError::ModificadorRepetido,
+                        ..
+                    })
+                ),
+                "{}",
+                token
+            );
+        }
+    }
+
+    #[test]
+    fn test_crear_jugador() {
+        use crate::modelo::jugador::Jugador;
+
+        let tile = super::crear_pieza("J", 3, 1);
+        assert_eq!(tile, Ok(Tile::Jugador(Jugador::crear(3, 1))));
+
+        let tile = super::crear_pieza("Jc2r3", 0, 0);
+        assert!(matches!(
+            tile,
+            Ok(Tile::Jugador(Jugador {
+                capacidad: 2,
+                radio: 3,
+                ..
+            }))
+        ));
+
+        let tile = super::crear_pieza("Jc0", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::CapacidadCero,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("Jr0", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::RadioCero,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("Jd2", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::ModificadorDesconocido,
+                ..
+            })
+        ));
+
+        let tile = super::crear_pieza("Jc2s1", 0, 0);
+        assert!(matches!(
+            tile,
+            Ok(Tile::Jugador(Jugador {
+                capacidad: 2,
+                especial: true,
+                ..
+            }))
+        ));
+
+        let tile = super::crear_pieza("Js2", 0, 0);
+        assert!(matches!(
+            tile,
+            Err(ParseError::Token {
+                tipo: TipoError::EspecialInvalido,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_crear_enemigo_con_movimiento() {
+        use crate::modelo::movimiento::Movimiento;
+
+        let tile = super::crear_pieza("F3p", 0, 0);
+        let mut enemigo = Enemigo::crear(0, 0, 3);
+        enemigo.movimiento = Movimiento::Perseguidor;
+        assert_eq!(tile, Ok(Tile::Enemigo(enemigo)));
+
+        let tile = super::crear_pieza("F1v", 0, 0);
+        assert!(matches!(
+            tile,
+            Ok(Tile::Enemigo(Enemigo {
+                movimiento: Movimiento::Patrulla(Direccion::Abajo),
+                ..
+            }))
+        ));
+
+        for (token, direccion) in [
+            ("F1h", Direccion::Derecha),
+            ("F1H", Direccion::Izquierda),
+            ("F1V", Direccion::Arriba),
+        ] {
+            let tile = super::crear_pieza(token, 0, 0);
+            let mut enemigo = Enemigo::crear(0, 0, 1);
+            enemigo.movimiento = Movimiento::Patrulla(direccion);
+            assert_eq!(tile, Ok(Tile::Enemigo(enemigo)), "{}", token);
+        }
+
+        for token in ["F1x", "F1z", "F12Z"] {
+            let tile = super::crear_pieza(token, 0, 0);
+            assert!(
+                matches!(
+                    tile,
+                    Err(ParseError::Token {
+                        tipo: TipoError::MovimientoInvalido,
+                        ..
+                    })
+                ),
+                "{}",
+                token
+            );
+        }
+        assert_eq!(
+            super::crear_pieza("F1z", 0, 0).unwrap_err().to_string(),
+            "fila 0, columna 0: >F1z<: movimiento invalido, debe ser a, h, H, v, V, p o e"
+        );
+        for token in ["F1pp", "Fp"] {
+            assert!(super::crear_pieza(token, 0, 0).is_err(), "{}", token);
+        }
+        assert!(matches!(
+            super::crear_pieza("F0a", 0, 0),
+            Err(ParseError::Token {
+                tipo: TipoError::VidaCero,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_crear_enemigo_con_impactos() {
+        let tile = super::crear_pieza("F2ai1,0i10,3", 4, 1);
+        let mut enemigo = Enemigo::crear(4, 1, 2);
+        enemigo.movimiento = crate::modelo::movimiento::Movimiento::Aleatorio;
+        enemigo.recibir_impacto(1, 0);
+        enemigo.recibir_impacto(10, 3);
+        assert_eq!(tile, Ok(Tile::Enemigo(enemigo)));
+
+        let tile = super::crear_pieza("F1i0,0", 1, 0);
+        assert!(matches!(tile, Ok(Tile::Enemigo(enemigo)) if enemigo.ya_impactado(0, 0)));
+
+        for token in ["F2i", "F2i1", "F2i1,", "F2i,3", "F2i1;2"] {
+            assert!(
+                matches!(
+                    super::crear_pieza(token, 0, 0),
+                    Err(ParseError::Token {
+                        tipo: TipoError::ImpactoInvalido,
+                        ..
+                    })
+                ),
+                "{}",
+                token
+            );
+        }
+        assert!(super::crear_pieza("F2i1,0x", 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_crear_piedra_y_premio() {
+        use crate::modelo::premio::Premio;
+
+        let tile = super::crear_pieza("Rb", 1, 2);
+        let mut piedra = Obstaculo::crear(1, 2, false);
+        piedra.premio = Some(Premio::BombaExtra);
+        assert_eq!(tile, Ok(Tile::Piedra(piedra)));
+
+        let tile = super::crear_pieza("Ps", 0, 0);
+        assert_eq!(tile, Ok(Tile::Premio(Premio::BombaEspecial)));
+
+        for token in ["Rx", "Rbb", "P", "Pd"] {
+            assert!(
+                matches!(
+                    super::crear_pieza(token, 0, 0),
+                    Err(ParseError::Token {
+                        tipo: TipoError::PremioInvalido,
+                        ..
+                    })
+                ),
+                "{}",
+                token
+            );
+        }
+    }
 }
@@ -0,0 +1,370 @@
+use super::{
+    bomba::{Bomba, DMG_POR_DEFECTO},
+    constantes::{
+        BOMBA_ESPECIAL, BOMBA_NORMAL, DESVIO, ENEMIGO, JUGADOR, MODIFICADOR_CAPACIDAD,
+        MODIFICADOR_DMG, MODIFICADOR_ESPECIAL, MODIFICADOR_IMPACTO, MODIFICADOR_MECHA,
+        MODIFICADOR_RADIO, PARED, PIEDRA, PREMIO, SEPARADOR_COORDENADAS, VACIO,
+    },
+    enemigo::Enemigo,
+    error::ParseError,
+    fabrica::crear_pieza,
+    jugador::{Jugador, CAPACIDAD_POR_DEFECTO, RADIO_POR_DEFECTO},
+    mapa::Mapa,
+    tile::Tile,
+};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+/// Escribe el token de una bomba: el simbolo seguido del radio, del daño si no es el de por defecto y de la mecha si tiene.
+/// Ejemplo: B3, S2d4, B1m3.
+fn escribir_bomba(f: &mut fmt::Formatter<'_>, simbolo: char, bomba: &Bomba) -> fmt::Result {
+    write!(f, "{}{}", simbolo, bomba.radio)?;
+    if bomba.dmg != DMG_POR_DEFECTO {
+        write!(f, "{}{}", MODIFICADOR_DMG, bomba.dmg)?;
+    }
+    if let Some(mecha) = bomba.mecha {
+        write!(f, "{}{}", MODIFICADOR_MECHA, mecha)?;
+    }
+    Ok(())
+}
+
+/// Escribe el token de un enemigo: el simbolo seguido de la vida, del movimiento si se mueve
+/// y de las bombas que ya lo impactaron, ordenadas por fila y columna. Ejemplo: F2, F3p, F2i1,0i0,2.
+fn escribir_enemigo(f: &mut fmt::Formatter<'_>, enemigo: &Enemigo) -> fmt::Result {
+    write!(f, "{}{}", ENEMIGO, enemigo.vida)?;
+    if let Some(c) = enemigo.movimiento.char_movimiento() {
+        write!(f, "{}", c)?;
+    }
+    let mut impactos: Vec<_> = enemigo.bombas_impactadas.iter().collect();
+    impactos.sort_by_key(|bomba| (bomba.y, bomba.x));
+    for bomba in impactos {
+        write!(
+            f,
+            "{}{}{}{}",
+            MODIFICADOR_IMPACTO, bomba.x, SEPARADOR_COORDENADAS, bomba.y
+        )?;
+    }
+    Ok(())
+}
+
+/// Escribe el token del jugador: el simbolo seguido de la capacidad y del radio si no son los de por defecto,
+/// y de s1 si pone bombas especiales. Ejemplo: J, Jc2, Jc2r3, Js1.
+fn escribir_jugador(f: &mut fmt::Formatter<'_>, jugador: &Jugador) -> fmt::Result {
+    write!(f, "{}", JUGADOR)?;
+    if jugador.capacidad != CAPACIDAD_POR_DEFECTO {
+        write!(f, "{}{}", MODIFICADOR_CAPACIDAD, jugador.capacidad)?;
+    }
+    if jugador.radio != RADIO_POR_DEFECTO {
+        write!(f, "{}{}", MODIFICADOR_RADIO, jugador.radio)?;
+    }
+    if jugador.especial {
+        write!(f, "{}1", MODIFICADOR_ESPECIAL)?;
+    }
+    Ok(())
+}
+
+/// Muestra el tile con el mismo token que se usa en los archivos de mapa. Ejemplo: F2, B3, DU, _.
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tile::Enemigo(enemigo) => escribir_enemigo(f, enemigo),
+            Tile::BombaNormal(bomba) => escribir_bomba(f, BOMBA_NORMAL, bomba),
+            Tile::BombaEspecial(bomba) => escribir_bomba(f, BOMBA_ESPECIAL, bomba),
+            Tile::Piedra(piedra) => {
+                write!(f, "{}", PIEDRA)?;
+                match piedra.premio {
+                    Some(premio) => write!(f, "{}", premio.a_char()),
+                    None => Ok(()),
+                }
+            }
+            Tile::Pared(_) => write!(f, "{}", PARED),
+            Tile::Desvio(desvio) => write!(f, "{}{}", DESVIO, desvio.char_direccion()),
+            Tile::Jugador(jugador) => escribir_jugador(f, jugador),
+            Tile::Premio(premio) => write!(f, "{}{}", PREMIO, premio.a_char()),
+            Tile::Vacio => write!(f, "{}", VACIO),
+        }
+    }
+}
+
+/// Muestra el mapa en el formato canonico de los archivos de mapa:
+/// una fila por linea, los tokens separados por un espacio y cada fila terminada en un salto de linea.
+/// Transformar el texto resultante devuelve un mapa igual, incluidas las bombas que ya impactaron a cada enemigo,
+/// el sentido en el que va cada patrulla y si el jugador pone bombas especiales.
+impl fmt::Display for Mapa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for fila in self.tiles.iter() {
+            for (x_pos, tile) in fila.iter().enumerate() {
+                if x_pos > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", tile)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Transforma una linea de texto en un vector de tiles.
+/// Para poder ser transformada, toda la linea tiene que venir con el formato correcto:
+/// <tipo<numero> <tipo><numero> <tipo><numero>
+/// Si no se pudo transformar la linea, devuelve un error.
+fn transformar_linea(s: &str, y_pos: usize) -> Result<Vec<Tile>, ParseError> {
+    let caracteres: Vec<&str> = s.trim().split(' ').filter(|x| !x.is_empty()).collect();
+    let mut tiles: Vec<Tile> = Vec::new();
+    for (x_pos, caracter) in caracteres.into_iter().enumerate() {
+        tiles.push(crear_pieza(caracter, x_pos, y_pos)?);
+    }
+    Ok(tiles)
+}
+
+/// Transforma las lineas de texto en un mapa, una fila por linea.
+fn transformar_lineas<I>(lineas: I) -> Result<Mapa, ParseError>
+where
+    I: Iterator<Item = io::Result<String>>,
+{
+    let mut mapa = Mapa::crear();
+
+    for (y_pos, linea) in lineas.enumerate() {
+        match linea {
+            Err(why) => {
+                return Err(ParseError::Lectura {
+                    fila: y_pos,
+                    mensaje: why.to_string(),
+                })
+            }
+            Ok(linea) => {
+                let tiles_temp = transformar_linea(&linea, y_pos)?;
+                if y_pos == 0 {
+                    mapa.ancho = tiles_temp.len();
+                }
+
+                if tiles_temp.len() != mapa.ancho {
+                    return Err(ParseError::FilaIrregular {
+                        fila: y_pos,
+                        esperadas: mapa.ancho,
+                        encontradas: tiles_temp.len(),
+                    });
+                }
+
+                mapa.tiles.push(tiles_temp);
+            }
+        }
+    }
+    mapa.alto = mapa.tiles.len();
+
+    if mapa.ancho == 0 || mapa.alto == 0 {
+        return Err(ParseError::MapaVacio);
+    }
+
+    Ok(mapa)
+}
+
+/// Transforma un texto en un mapa, una fila por linea.
+/// Todas las filas deben tener la misma cantidad de columnas, pero el mapa no necesita ser cuadrado.
+/// Si no se pudo transformar el texto, devuelve un error indicando en que fila y columna ocurrio.
+impl FromStr for Mapa {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        transformar_lineas(s.lines().map(|linea| Ok(linea.to_string())))
+    }
+}
+
+impl Mapa {
+    /// Lee un mapa de cualquier lector, con el mismo formato que from_str.
+    /// Si no se pudo leer alguna linea, devuelve un error indicando en que fila ocurrio.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Mapa, ParseError> {
+        transformar_lineas(reader.lines())
+    }
+
+    /// Escribe el mapa en formato canonico. Devuelve el error si no se pudo escribir.
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{}", self)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bomberman_game::transformar_a_mapa;
+    use crate::modelo::{
+        desvio::Desvio, direccion::Direccion, enemigo::Enemigo, error::TipoError,
+        obstaculo::Obstaculo,
+    };
+
+    #[test]
+    fn test_transformar_linea() {
+        let tiles = transformar_linea("F1 _ _ B3 _ R W", 0);
+        assert!(tiles.is_ok());
+        let tiles = tiles.unwrap();
+        assert_eq!(tiles.len(), 7);
+        assert_eq!(tiles[0], Tile::Enemigo(Enemigo::crear(0, 0, 1)));
+        assert_eq!(tiles[1], Tile::Vacio);
+        assert_eq!(tiles[2], Tile::Vacio);
+        assert_eq!(tiles[3], Tile::BombaNormal(Bomba::crear(3, 0, 3, false)));
+        assert_eq!(tiles[4], Tile::Vacio);
+        assert_eq!(tiles[5], Tile::Piedra(Obstaculo::crear(5, 0, false)));
+        assert_eq!(tiles[6], Tile::Pared(Obstaculo::crear(6, 0, true)));
+    }
+
+    #[test]
+    fn test_mapa_desde_string() {
+        let mapa: Mapa = "F2 _ B1\n_ W DU\n".parse().unwrap();
+        assert_eq!(mapa.ancho, 3);
+        assert_eq!(mapa.alto, 2);
+        assert_eq!(mapa.tiles[0][0], Tile::Enemigo(Enemigo::crear(0, 0, 2)));
+        assert_eq!(
+            mapa.tiles[0][2],
+            Tile::BombaNormal(Bomba::crear(2, 0, 1, false))
+        );
+        assert_eq!(mapa.tiles[1][1], Tile::Pared(Obstaculo::crear(1, 1, true)));
+
+        assert_eq!("".parse::<Mapa>(), Err(ParseError::MapaVacio));
+        assert_eq!(
+            "F1 _\n_".parse::<Mapa>(),
+            Err(ParseError::FilaIrregular {
+                fila: 1,
+                esperadas: 2,
+                encontradas: 1
+            })
+        );
+        assert!(matches!(
+            "F1 _\n_ X1".parse::<Mapa>(),
+            Err(ParseError::Token {
+                fila: 1,
+                columna: 1,
+                tipo: TipoError::PiezaDesconocida,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_mapa_desde_reader() {
+        let texto = "F1 _ _ B3 _ R W\n".repeat(7);
+        let mapa = Mapa::from_reader(texto.as_bytes()).unwrap();
+        assert_eq!(
+            mapa,
+            transformar_a_mapa("mapas/mapa_test_crear.txt").unwrap()
+        );
+        assert_eq!(mapa, texto.parse().unwrap());
+    }
+
+    #[test]
+    fn test_ida_y_vuelta_de_los_mapas_de_ejemplo() {
+        let invalidos = [
+            "mapa_test_filas_irregulares.txt",
+            "mapa_test_token_invalido.txt",
+        ];
+        let mut probados = 0;
+        for entrada in std::fs::read_dir("mapas").unwrap() {
+            let nombre = entrada.unwrap().file_name().into_string().unwrap();
+            // Los archivos *_guardar* los escriben otros tests mientras este corre.
+            if !nombre.ends_with(".txt")
+                || nombre.contains("_guardar")
+                || invalidos.contains(&nombre.as_str())
+            {
+                continue;
+            }
+            let mapa = transformar_a_mapa(&format!("mapas/{}", nombre)).unwrap();
+            assert_eq!(mapa.to_string().parse(), Ok(mapa), "{}", nombre);
+            probados += 1;
+        }
+        assert!(probados >= 10);
+    }
+
+    #[test]
+    fn test_ida_y_vuelta_a_mitad_de_partida() {
+        let mut mapa: Mapa = "F3 B1 _\nB1 S1d2 _\n_ _ F2a".parse().unwrap();
+        crate::turno::jugar_turno(&mut mapa, 1, 0).unwrap();
+        let texto = mapa.to_string();
+        assert_eq!(texto, "F1i1,0i0,1 _ _\n_ _ _\n_ _ F2a\n");
+        assert_eq!(texto.parse(), Ok(mapa));
+    }
+
+    #[test]
+    fn test_ida_y_vuelta_de_patrulla_dada_vuelta_y_jugador_especial() {
+        use crate::modelo::movimiento::Movimiento;
+        use crate::partida::{ModoCadena, Partida};
+
+        let mut partida = Partida::crear("F1h _ _ W".parse().unwrap(), ModoCadena::Instantanea);
+        for _ in 0..3 {
+            partida.avanzar();
+        }
+        let mut mapa = partida.mapa;
+        assert!(matches!(
+            mapa.obtener_tile(1, 0),
+            Some(Tile::Enemigo(enemigo)) if enemigo.movimiento == Movimiento::Patrulla(Direccion::Izquierda)
+        ));
+        let mut jugador = Jugador::crear(3, 0);
+        jugador.especial = true;
+        mapa.tiles[0][3] = Tile::Jugador(jugador);
+
+        let texto = mapa.to_string();
+        assert_eq!(texto, "_ F1H _ Js1\n");
+        assert_eq!(texto.parse(), Ok(mapa));
+    }
+
+    #[test]
+    fn test_mostrar_mapa() {
+        let mapa = Mapa {
+            ancho: 4,
+            alto: 2,
+            tiles: vec![
+                vec![
+                    Tile::Enemigo(Enemigo::crear(0, 0, 12)),
+                    Tile::BombaNormal(Bomba::crear(1, 0, 3, false)),
+                    Tile::BombaEspecial(Bomba::crear_con_dmg(2, 0, 2, true, 4)),
+                    Tile::Vacio,
+                ],
+                vec![
+                    Tile::Piedra(Obstaculo::crear(0, 1, false)),
+                    Tile::Pared(Obstaculo::crear(1, 1, true)),
+                    Tile::Desvio(Desvio {
+                        x: 2,
+                        y: 1,
+                        direccion: Direccion::Izquierda,
+                    }),
+                    Tile::Vacio,
+                ],
+            ],
+        };
+        assert_eq!(mapa.to_string(), "F12 B3 S2d4 _\nR W DL _\n");
+        assert_eq!(
+            "B1m3 S2d2m1".parse::<Mapa>().unwrap().to_string(),
+            "B1m3 S2d2m1\n"
+        );
+
+        let mut escrito = Vec::new();
+        mapa.write_to(&mut escrito).unwrap();
+        assert_eq!(escrito, mapa.to_string().into_bytes());
+    }
+
+    #[test]
+    fn test_ida_y_vuelta() {
+        for path in [
+            "mapas/mapa_1.txt",
+            "mapas/mapa_2.txt",
+            "mapas/mapa_3.txt",
+            "mapas/mapa_4.txt",
+            "mapas/mapa_5.txt",
+            "mapas/mapa_test_varios_digitos.txt",
+        ] {
+            let mapa = transformar_a_mapa(path).unwrap();
+            let texto = mapa.to_string();
+            assert_eq!(texto.parse::<Mapa>(), Ok(mapa), "{}", path);
+            assert!(texto.lines().all(|linea| !linea.ends_with(' ')));
+        }
+    }
+
+    #[test]
+    fn test_mostrar_jugador_movimientos_y_premios() {
+        let texto = "J _ Jc2\nJr3 Jc2r3 B1\nF1a F2h F3v\nF4p F5e F6\nRb Rr Rs\nPb Pr Ps\n";
+        let mapa: Mapa = texto.parse().unwrap();
+        assert_eq!(mapa.to_string(), texto);
+        assert_eq!("Jc1r1".parse::<Mapa>().unwrap().to_string(), "J\n");
+    }
+}
@@ -0,0 +1,87 @@
+use super::{bomba::Bomba, coordenada::Coordenada, premio::Premio};
+
+/// Cantidad de bombas que el jugador puede tener en el mapa a la vez si el mapa no indica otra.
+pub const CAPACIDAD_POR_DEFECTO: u32 = 1;
+/// Radio de las bombas del jugador si el mapa no indica otro.
+pub const RADIO_POR_DEFECTO: u32 = 1;
+/// Ticks que tarda en explotar una bomba puesta por el jugador.
+pub const MECHA_BOMBA_JUGADOR: u32 = 3;
+
+/// Jugador que se mueve por el mapa y pone bombas.
+/// Puede tener hasta capacidad bombas puestas a la vez, todas de radio radio y especiales si especial=true.
+/// Si lo alcanza una explosion, deja de estar vivo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Jugador {
+    pub x: usize,
+    pub y: usize,
+    pub capacidad: u32,
+    pub radio: u32,
+    pub especial: bool,
+    pub vivo: bool,
+}
+
+impl Jugador {
+    /// Crea un jugador vivo con la capacidad y el radio por defecto, que pone bombas normales.
+    pub fn crear(x: usize, y: usize) -> Jugador {
+        Jugador {
+            x,
+            y,
+            capacidad: CAPACIDAD_POR_DEFECTO,
+            radio: RADIO_POR_DEFECTO,
+            especial: false,
+            vivo: true,
+        }
+    }
+
+    /// Devuelve las coordenadas del jugador.
+    pub fn coordenadas(&self) -> Coordenada {
+        Coordenada {
+            x: self.x,
+            y: self.y,
+        }
+    }
+
+    /// Crea una bomba en la posicion del jugador, con su radio y la mecha de las bombas del jugador.
+    pub fn crear_bomba(&self) -> Bomba {
+        let mut bomba = Bomba::crear(self.x, self.y, self.radio, self.especial);
+        bomba.mecha = Some(MECHA_BOMBA_JUGADOR);
+        bomba
+    }
+
+    /// Aplica la mejora del premio.
+    pub fn recoger(&mut self, premio: Premio) {
+        match premio {
+            Premio::BombaExtra => self.capacidad = self.capacidad.saturating_add(1),
+            Premio::MasRadio => self.radio = self.radio.saturating_add(1),
+            Premio::BombaEspecial => self.especial = true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_crear_bomba() {
+        let mut jugador = Jugador::crear(2, 1);
+        jugador.radio = 3;
+        let bomba = jugador.crear_bomba();
+        assert_eq!(bomba.coordenadas(), jugador.coordenadas());
+        assert_eq!(bomba.radio, 3);
+        assert!(!bomba.especial);
+        assert_eq!(bomba.mecha, Some(MECHA_BOMBA_JUGADOR));
+    }
+
+    #[test]
+    fn test_recoger_premios() {
+        let mut jugador = Jugador::crear(0, 0);
+        jugador.recoger(Premio::BombaExtra);
+        jugador.recoger(Premio::MasRadio);
+        jugador.recoger(Premio::MasRadio);
+        assert_eq!((jugador.capacidad, jugador.radio), (2, 3));
+        assert!(!jugador.crear_bomba().especial);
+        jugador.recoger(Premio::BombaEspecial);
+        assert!(jugador.crear_bomba().especial);
+    }
+}
@@ -1,11 +1,11 @@
-use super::{coordenada::Coordenada, direccion, tile::Tile};
-
-const DMG_A_ENEMIGOS: u32 = 1;
+use super::{alcance::Alcance, coordenada::Coordenada, direccion::Direccion, tile::Tile};
+use std::collections::HashSet;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Mapa {
     pub tiles: Vec<Vec<Tile>>,
-    pub side_size: usize,
+    pub ancho: usize,
+    pub alto: usize,
 }
 
 impl Mapa {
@@ -14,7 +14,8 @@ impl Mapa {
     pub fn crear() -> Self {
         Mapa {
             tiles: Vec::new(),
-            side_size: 0,
+            ancho: 0,
+            alto: 0,
         }
     }
     /// Devuelve un tile si es que se cumplen las condiciones correctas:
@@ -23,7 +24,7 @@ impl Mapa {
     ///   - Si la tile es una roca y especial fue seteado en false.
     fn chequear_tile(&self, x_pos: usize, y_pos: usize, especial: bool) -> Option<&Tile> {
         if let Some(tile) = self.obtener_tile(x_pos, y_pos) {
-            if matches!(tile, Tile::Piedra(_)) && !especial | matches!(tile, Tile::Pared(_)) {
+            if (matches!(tile, Tile::Piedra(_)) && !especial) || matches!(tile, Tile::Pared(_)) {
                 return None;
             }
             return Some(tile);
@@ -33,7 +34,7 @@ impl Mapa {
 
     /// Devuelve si la coordenada esta por fuera del mapa.
     fn esta_fuera_de_rango(&self, x: i32, y: i32) -> bool {
-        x < 0 || x >= self.side_size as i32 || y < 0 || y >= self.side_size as i32
+        x < 0 || x >= self.ancho as i32 || y < 0 || y >= self.alto as i32
     }
 
     /// Devuelve un vector de coordenadas que representan las tiles que se encuentran en el alcance de la bomba en una direccion dada por el vector (dx, dy).
@@ -51,64 +52,102 @@ impl Mapa {
         dx: i32,
         dy: i32,
     ) -> Vec<Coordenada> {
-        let mut tiles_encontradas: Vec<Coordenada> = Vec::new();
-        let mut x = x_pos as i32;
-        let mut y = y_pos as i32;
-        for _ in 0..alcance {
-            x += dx;
-            y += dy;
-            if self.esta_fuera_de_rango(x, y) {
-                break;
-            }
-            match self.chequear_tile(x as usize, y as usize, especial) {
-                None => break,
-                Some(Tile::Desvio(_)) => {
-                    let faltante = alcance - tiles_encontradas.len();
-                    tiles_encontradas
-                        .append(&mut self.desviar(x as usize, y as usize, faltante, especial));
-                    break;
-                }
-                Some(_) => tiles_encontradas.push(Coordenada {
-                    x: x as usize,
-                    y: y as usize,
-                }),
-            }
-        }
-        tiles_encontradas
-        // cargo fmt hace que supere las 30 lineas.
+        self.recorrer_en_direccion(x_pos, y_pos, alcance, especial, dx, dy)
+            .into_iter()
+            .map(|alcanzado| alcanzado.coordenada)
+            .collect()
     }
 
-    /// Ejecuta un desvio segun la direccion y sigue buscando en ese sentido.
-    fn desviar(
+    /// Igual que buscar_en_direccion, pero por cada tile alcanzada devuelve tambien los desvios que atraveso la explosion para llegar.
+    /// Cada casilla recorrida, incluidos los desvios, consume un paso del alcance.
+    /// Al pasar por un desvio la explosion sigue desde esa casilla en la direccion que indica el desvio.
+    /// Si la explosion vuelve a pasar por un desvio que ya atraveso, entro en un ciclo y se detiene ahi.
+    pub fn recorrer_en_direccion(
         &self,
         x_pos: usize,
         y_pos: usize,
         alcance: usize,
         especial: bool,
-    ) -> Vec<Coordenada> {
-        if let Some(Tile::Desvio(desvio)) = self.obtener_tile(x_pos, y_pos) {
-            match desvio.direccion {
-                direccion::Direccion::Arriba => {
-                    return self.buscar_en_direccion(x_pos, y_pos - 1, alcance, especial, 0, -1);
-                }
-                direccion::Direccion::Abajo => {
-                    return self.buscar_en_direccion(x_pos, y_pos + 1, alcance, especial, 0, 1);
-                }
-                direccion::Direccion::Izquierda => {
-                    return self.buscar_en_direccion(x_pos - 1, y_pos, alcance, especial, -1, 0);
+        dx: i32,
+        dy: i32,
+    ) -> Vec<Alcance> {
+        self.recorrer(x_pos, y_pos, alcance, especial, dx, dy).0
+    }
+
+    /// Devuelve la piedra en la que se detuvo la explosion de una bomba normal en la direccion (dx, dy), con los desvios que atraveso para llegar.
+    /// Devuelve None si la explosion se detuvo por otro motivo, o si la bomba es especial, porque las especiales no se detienen en las piedras.
+    pub fn piedra_en_direccion(
+        &self,
+        x_pos: usize,
+        y_pos: usize,
+        alcance: usize,
+        especial: bool,
+        dx: i32,
+        dy: i32,
+    ) -> Option<Alcance> {
+        self.recorrer(x_pos, y_pos, alcance, especial, dx, dy).1
+    }
+
+    /// Recorre la explosion como recorrer_en_direccion.
+    /// Ademas de las casillas alcanzadas, devuelve la piedra que la detuvo, si fue una piedra.
+    fn recorrer(
+        &self,
+        x_pos: usize,
+        y_pos: usize,
+        alcance: usize,
+        especial: bool,
+        dx: i32,
+        dy: i32,
+    ) -> (Vec<Alcance>, Option<Alcance>) {
+        let mut tiles_encontradas: Vec<Alcance> = Vec::new();
+        let mut desvios: Vec<Coordenada> = Vec::new();
+        let mut desvios_visitados = HashSet::new();
+        let mut paso = (dx, dy);
+        let mut x = x_pos as i32;
+        let mut y = y_pos as i32;
+        for _ in 0..alcance {
+            x += paso.0;
+            y += paso.1;
+            if self.esta_fuera_de_rango(x, y) {
+                break;
+            }
+            let coordenada = Coordenada {
+                x: x as usize,
+                y: y as usize,
+            };
+            match self.chequear_tile(coordenada.x, coordenada.y, especial) {
+                None => {
+                    if let Some(Tile::Piedra(_)) = self.obtener_tile(coordenada.x, coordenada.y) {
+                        return (
+                            tiles_encontradas,
+                            Some(Alcance {
+                                coordenada,
+                                desvios,
+                            }),
+                        );
+                    }
+                    break;
                 }
-                direccion::Direccion::Derecha => {
-                    return self.buscar_en_direccion(x_pos + 1, y_pos, alcance, especial, 1, 0);
+                Some(Tile::Desvio(desvio)) => {
+                    if !desvios_visitados.insert(coordenada) {
+                        break;
+                    }
+                    desvios.push(coordenada);
+                    paso = desvio.direccion.desplazamiento();
                 }
+                Some(_) => tiles_encontradas.push(Alcance {
+                    coordenada,
+                    desvios: desvios.clone(),
+                }),
             }
         }
-        Vec::new()
+        (tiles_encontradas, None)
     }
 
     /// Devuelve la referencia al tile en la posicion (x_pos, y_pos) si existe, caso contrario None.
     /// Si la posicion esta fuera del mapa, devuelve None.
     pub fn obtener_tile(&self, x_pos: usize, y_pos: usize) -> Option<&Tile> {
-        if x_pos >= self.side_size || y_pos >= self.side_size {
+        if x_pos >= self.ancho || y_pos >= self.alto {
             return None;
         }
         Some(&self.tiles[y_pos][x_pos])
@@ -116,24 +155,44 @@ impl Mapa {
 
     /// Devuelve la referencia mutable al tile en la posicion (x_pos, y_pos) si existe, caso contrario None.
     /// Si la posicion esta fuera del mapa, devuelve None.
-    fn obtener_tile_mut(&mut self, x_pos: usize, y_pos: usize) -> Option<&mut Tile> {
-        if x_pos >= self.side_size || y_pos >= self.side_size {
+    pub fn obtener_tile_mut(&mut self, x_pos: usize, y_pos: usize) -> Option<&mut Tile> {
+        if x_pos >= self.ancho || y_pos >= self.alto {
             return None;
         }
         Some(&mut self.tiles[y_pos][x_pos])
     }
 
+    /// Devuelve la casilla vecina a (x_pos, y_pos) en la direccion dada, o None si queda fuera del mapa.
+    pub fn vecina(&self, x_pos: usize, y_pos: usize, direccion: Direccion) -> Option<Coordenada> {
+        let (dx, dy) = direccion.desplazamiento();
+        let x = x_pos.checked_add_signed(dx as isize)?;
+        let y = y_pos.checked_add_signed(dy as isize)?;
+        self.obtener_tile(x, y)?;
+        Some(Coordenada { x, y })
+    }
+
+    /// Devuelve si se puede caminar sobre la casilla (x_pos, y_pos).
+    /// Solo las casillas vacias, los desvios y los premios son transitables; paredes, piedras, bombas, enemigos y jugadores bloquean el paso.
+    /// Si la posicion esta fuera del mapa, devuelve false.
+    pub fn es_transitable(&self, x_pos: usize, y_pos: usize) -> bool {
+        matches!(
+            self.obtener_tile(x_pos, y_pos),
+            Some(Tile::Vacio | Tile::Desvio(_) | Tile::Premio(_))
+        )
+    }
+
     /// Destruye el tile en la posicion (x_pos, y_pos), poniendo un Tile Vacio en su lugar.
     /// Si la posicion esta fuera del mapa, no hace nada.
     pub fn destruir_tile(&mut self, x_pos: usize, y_pos: usize) {
-        if x_pos >= self.side_size || y_pos >= self.side_size {
+        if x_pos >= self.ancho || y_pos >= self.alto {
             return;
         }
         self.tiles[y_pos][x_pos] = Tile::Vacio;
     }
 
-    /// Recibe las coordenadas de una bomba y la posicion que se debe atacar, si hay un enemigo en esa posicion, le descuenta vida.
-    /// Si la vida del enemigo es menor o igual a 0, destruye el tile.
+    /// Recibe las coordenadas de una bomba y la posicion que se debe atacar, si hay un enemigo en esa posicion, le descuenta dmg de vida.
+    /// Si la vida del enemigo llega a 0, destruye el tile.
+    /// Devuelve la vida restante del enemigo si fue atacado, o None si no habia enemigo o esa bomba ya lo habia impactado.
     pub fn atacar_enemigo(
         &mut self,
         bomba_x: usize,
@@ -141,23 +200,28 @@ impl Mapa {
         x_pos: usize,
         y_pos: usize,
         dmg: u32,
-    ) {
+    ) -> Option<u32> {
         if let Some(Tile::Enemigo(enemigo)) = self.obtener_tile_mut(x_pos, y_pos) {
             if !enemigo.ya_impactado(bomba_x, bomba_y) {
-                if enemigo.vida <= dmg {
+                enemigo.recibir_impacto(bomba_x, bomba_y);
+                enemigo.descontar_vida(dmg);
+                let vida_restante = enemigo.vida;
+                if vida_restante == 0 {
                     self.destruir_tile(x_pos, y_pos);
-                } else {
-                    enemigo.recibir_impacto(bomba_x, bomba_y);
-                    enemigo.descontar_vida(DMG_A_ENEMIGOS);
                 }
+                return Some(vida_restante);
             }
         }
+        None
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::modelo::{
+        desvio::Desvio, direccion::Direccion, enemigo::Enemigo, obstaculo::Obstaculo,
+    };
 
     #[test]
     fn test_mapa_obtener_tile() {
@@ -166,7 +230,8 @@ mod test {
                 vec![Tile::Vacio, Tile::Vacio],
                 vec![Tile::Vacio, Tile::Vacio],
             ],
-            side_size: 2,
+            ancho: 2,
+            alto: 2,
         };
         assert_eq!(mapa.obtener_tile(0, 0), Some(&Tile::Vacio));
         assert_eq!(mapa.obtener_tile(1, 0), Some(&Tile::Vacio));
@@ -182,7 +247,8 @@ mod test {
                 vec![Tile::Vacio, Tile::Vacio],
                 vec![Tile::Vacio, Tile::Vacio],
             ],
-            side_size: 2,
+            ancho: 2,
+            alto: 2,
         };
         assert_eq!(mapa.obtener_tile_mut(0, 0), Some(&mut Tile::Vacio));
         assert_eq!(mapa.obtener_tile_mut(1, 0), Some(&mut Tile::Vacio));
@@ -198,7 +264,8 @@ mod test {
                 vec![Tile::Vacio, Tile::Vacio],
                 vec![Tile::Vacio, Tile::Vacio],
             ],
-            side_size: 2,
+            ancho: 2,
+            alto: 2,
         };
         mapa.destruir_tile(0, 0);
         assert_eq!(mapa.obtener_tile(0, 0), Some(&Tile::Vacio));
@@ -216,11 +283,249 @@ mod test {
                 vec![Tile::Vacio, Tile::Vacio, Tile::Vacio],
                 vec![Tile::Vacio, Tile::Vacio, Tile::Vacio],
             ],
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
         };
         let tiles = mapa.buscar_en_direccion(1, 2, 2, false, 0, -1);
         assert_eq!(tiles.len(), 2);
         assert_eq!(tiles[0], Coordenada { x: 1, y: 1 });
         assert_eq!(tiles[1], Coordenada { x: 1, y: 0 });
     }
+
+    #[test]
+    fn test_buscar_tiles_mapa_rectangular() {
+        let mapa = Mapa {
+            tiles: vec![
+                vec![Tile::Vacio, Tile::Vacio, Tile::Vacio, Tile::Vacio],
+                vec![Tile::Vacio, Tile::Vacio, Tile::Vacio, Tile::Vacio],
+            ],
+            ancho: 4,
+            alto: 2,
+        };
+        assert_eq!(mapa.obtener_tile(3, 1), Some(&Tile::Vacio));
+        assert_eq!(mapa.obtener_tile(1, 3), None);
+
+        let tiles = mapa.buscar_en_direccion(0, 0, 5, false, 1, 0);
+        assert_eq!(
+            tiles,
+            vec![
+                Coordenada { x: 1, y: 0 },
+                Coordenada { x: 2, y: 0 },
+                Coordenada { x: 3, y: 0 }
+            ]
+        );
+        let tiles = mapa.buscar_en_direccion(0, 0, 5, false, 0, 1);
+        assert_eq!(tiles, vec![Coordenada { x: 0, y: 1 }]);
+    }
+
+    #[test]
+    fn test_desvio_consume_un_paso() {
+        let mut tiles = vec![vec![Tile::Vacio; 3]; 4];
+        tiles[0][1] = Tile::Desvio(Desvio {
+            x: 1,
+            y: 0,
+            direccion: Direccion::Abajo,
+        });
+        let mapa = Mapa {
+            tiles,
+            ancho: 3,
+            alto: 4,
+        };
+        let tiles = mapa.recorrer_en_direccion(0, 0, 3, false, 1, 0);
+        assert_eq!(
+            tiles,
+            vec![
+                Alcance {
+                    coordenada: Coordenada { x: 1, y: 1 },
+                    desvios: vec![Coordenada { x: 1, y: 0 }]
+                },
+                Alcance {
+                    coordenada: Coordenada { x: 1, y: 2 },
+                    desvios: vec![Coordenada { x: 1, y: 0 }]
+                },
+            ]
+        );
+    }
+
+    /// Antes el faltante despues de un desvio se calculaba con las tiles encontradas en lugar de los pasos dados,
+    /// y la explosion salteaba la casilla siguiente al desvio: aca alcanzaba (1, 0), (2, 2) y (2, 3).
+    #[test]
+    fn test_pasos_despues_de_un_desvio() {
+        let mut tiles = vec![vec![Tile::Vacio; 3]; 4];
+        tiles[0][2] = Tile::Desvio(Desvio {
+            x: 2,
+            y: 0,
+            direccion: Direccion::Abajo,
+        });
+        let mapa = Mapa {
+            tiles,
+            ancho: 3,
+            alto: 4,
+        };
+        let tiles = mapa.buscar_en_direccion(0, 0, 3, false, 1, 0);
+        assert_eq!(
+            tiles,
+            vec![Coordenada { x: 1, y: 0 }, Coordenada { x: 2, y: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_ciclo_de_desvios() {
+        let mapa = Mapa {
+            tiles: vec![vec![
+                Tile::Vacio,
+                Tile::Desvio(Desvio {
+                    x: 1,
+                    y: 0,
+                    direccion: Direccion::Derecha,
+                }),
+                Tile::Vacio,
+                Tile::Desvio(Desvio {
+                    x: 3,
+                    y: 0,
+                    direccion: Direccion::Izquierda,
+                }),
+            ]],
+            ancho: 4,
+            alto: 1,
+        };
+        let tiles = mapa.recorrer_en_direccion(0, 0, 20, false, 1, 0);
+        let desvio_derecha = Coordenada { x: 1, y: 0 };
+        let desvio_izquierda = Coordenada { x: 3, y: 0 };
+        assert_eq!(
+            tiles,
+            vec![
+                Alcance {
+                    coordenada: Coordenada { x: 2, y: 0 },
+                    desvios: vec![desvio_derecha]
+                },
+                Alcance {
+                    coordenada: Coordenada { x: 2, y: 0 },
+                    desvios: vec![desvio_derecha, desvio_izquierda]
+                },
+            ]
+        );
+    }
+
+    /// Crea un mapa de 3x3 vacio con un desvio en (x, y).
+    fn mapa_con_desvio(x: usize, y: usize, direccion: Direccion) -> Mapa {
+        let mut tiles = vec![vec![Tile::Vacio; 3]; 3];
+        tiles[y][x] = Tile::Desvio(Desvio { x, y, direccion });
+        Mapa {
+            tiles,
+            ancho: 3,
+            alto: 3,
+        }
+    }
+
+    #[test]
+    fn test_desvio_izquierda_en_borde_izquierdo() {
+        let mapa = mapa_con_desvio(0, 1, Direccion::Izquierda);
+        let tiles = mapa.buscar_en_direccion(2, 1, 5, false, -1, 0);
+        assert_eq!(tiles, vec![Coordenada { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn test_desvio_arriba_en_borde_superior() {
+        let mapa = mapa_con_desvio(1, 0, Direccion::Arriba);
+        let tiles = mapa.buscar_en_direccion(1, 2, 5, false, 0, -1);
+        assert_eq!(tiles, vec![Coordenada { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn test_desvio_derecha_en_borde_derecho() {
+        let mapa = mapa_con_desvio(2, 1, Direccion::Derecha);
+        let tiles = mapa.buscar_en_direccion(0, 1, 5, false, 1, 0);
+        assert_eq!(tiles, vec![Coordenada { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn test_desvio_abajo_en_borde_inferior() {
+        let mapa = mapa_con_desvio(1, 2, Direccion::Abajo);
+        let tiles = mapa.buscar_en_direccion(1, 0, 5, false, 0, 1);
+        assert_eq!(tiles, vec![Coordenada { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn test_desvio_en_esquina_se_desvia_hacia_adentro() {
+        let mapa = mapa_con_desvio(0, 0, Direccion::Abajo);
+        let tiles = mapa.buscar_en_direccion(2, 0, 5, false, -1, 0);
+        assert_eq!(
+            tiles,
+            vec![
+                Coordenada { x: 1, y: 0 },
+                Coordenada { x: 0, y: 1 },
+                Coordenada { x: 0, y: 2 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_atacar_enemigo_con_dmg() {
+        let mut mapa = Mapa {
+            tiles: vec![vec![Tile::Enemigo(Enemigo::crear(0, 0, 5))]],
+            ancho: 1,
+            alto: 1,
+        };
+        assert_eq!(mapa.atacar_enemigo(1, 0, 0, 0, 3), Some(2));
+        assert_eq!(mapa.atacar_enemigo(1, 0, 0, 0, 3), None);
+        assert_eq!(mapa.atacar_enemigo(2, 0, 0, 0, 3), Some(0));
+        assert_eq!(mapa.obtener_tile(0, 0), Some(&Tile::Vacio));
+    }
+
+    #[test]
+    fn test_pared_detiene_bombas_normales_y_especiales() {
+        let mapa = Mapa {
+            tiles: vec![vec![
+                Tile::Vacio,
+                Tile::Vacio,
+                Tile::Pared(Obstaculo::crear(2, 0, true)),
+                Tile::Vacio,
+            ]],
+            ancho: 4,
+            alto: 1,
+        };
+        let tiles = mapa.buscar_en_direccion(0, 0, 5, false, 1, 0);
+        assert_eq!(tiles, vec![Coordenada { x: 1, y: 0 }]);
+        let tiles = mapa.buscar_en_direccion(0, 0, 5, true, 1, 0);
+        assert_eq!(tiles, vec![Coordenada { x: 1, y: 0 }]);
+    }
+
+    #[test]
+    fn test_vecina_y_transitable() {
+        let mapa: Mapa = "_ W R\nDU B1 F1".parse().unwrap();
+        assert_eq!(
+            mapa.vecina(0, 0, Direccion::Abajo),
+            Some(Coordenada { x: 0, y: 1 })
+        );
+        assert_eq!(mapa.vecina(0, 0, Direccion::Arriba), None);
+        assert_eq!(mapa.vecina(2, 1, Direccion::Derecha), None);
+        assert!(mapa.es_transitable(0, 0));
+        assert!(mapa.es_transitable(0, 1));
+        for (x, y) in [(1, 0), (2, 0), (1, 1), (2, 1), (3, 0)] {
+            assert!(!mapa.es_transitable(x, y), "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_piedra_en_direccion() {
+        let mapa: Mapa = "B3 _ R _\n_ W _ _\nDR R _ _".parse().unwrap();
+        assert_eq!(
+            mapa.piedra_en_direccion(0, 0, 3, false, 1, 0),
+            Some(Alcance {
+                coordenada: Coordenada { x: 2, y: 0 },
+                desvios: vec![]
+            })
+        );
+        assert_eq!(mapa.piedra_en_direccion(0, 0, 1, false, 1, 0), None);
+        assert_eq!(mapa.piedra_en_direccion(0, 0, 3, true, 1, 0), None);
+        assert_eq!(
+            mapa.piedra_en_direccion(0, 0, 3, false, 0, 1),
+            Some(Alcance {
+                coordenada: Coordenada { x: 1, y: 2 },
+                desvios: vec![Coordenada { x: 0, y: 2 }]
+            })
+        );
+        assert_eq!(mapa.piedra_en_direccion(1, 2, 3, false, 0, -1), None);
+    }
 }
@@ -1,10 +1,17 @@
+pub mod alcance;
 pub mod bomba;
 pub mod constantes;
 pub mod coordenada;
 pub mod desvio;
 pub mod direccion;
 pub mod enemigo;
+pub mod error;
+pub mod evento;
 pub mod fabrica;
+pub mod formato;
+pub mod jugador;
 pub mod mapa;
+pub mod movimiento;
 pub mod obstaculo;
+pub mod premio;
 pub mod tile;
@@ -0,0 +1,58 @@
+use super::{
+    constantes::{
+        MOVIMIENTO_ALEATORIO, MOVIMIENTO_HUIDIZO, MOVIMIENTO_PATRULLA_ARRIBA,
+        MOVIMIENTO_PATRULLA_HORIZONTAL, MOVIMIENTO_PATRULLA_IZQUIERDA,
+        MOVIMIENTO_PATRULLA_VERTICAL, MOVIMIENTO_PERSEGUIDOR,
+    },
+    direccion::Direccion,
+};
+
+/// Forma en la que se mueve un enemigo en cada tick de una Partida.
+/// Solo guarda el tipo y su estado; la decision de hacia donde moverse la toma el comportamiento de ia.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Movimiento {
+    /// No se mueve. Es el movimiento de los enemigos de los mapas originales.
+    Quieto,
+    /// Se mueve a una casilla libre vecina elegida al azar.
+    Aleatorio,
+    /// Va y viene por su fila o su columna. Guarda la direccion en la que se esta moviendo.
+    Patrulla(Direccion),
+    /// Se acerca al jugador por el camino mas corto.
+    Perseguidor,
+    /// Si esta al alcance de alguna bomba, se aleja hacia la casilla segura mas cercana.
+    Huidizo,
+}
+
+impl Movimiento {
+    /// Devuelve el movimiento que corresponde al sufijo de un enemigo en los mapas.
+    /// a: Aleatorio, h: Patrulla hacia la derecha, H: Patrulla hacia la izquierda,
+    /// v: Patrulla hacia abajo, V: Patrulla hacia arriba, p: Perseguidor, e: Huidizo.
+    /// Cualquier otro caracter devuelve None.
+    pub fn desde_char(c: char) -> Option<Movimiento> {
+        match c {
+            MOVIMIENTO_ALEATORIO => Some(Movimiento::Aleatorio),
+            MOVIMIENTO_PATRULLA_HORIZONTAL => Some(Movimiento::Patrulla(Direccion::Derecha)),
+            MOVIMIENTO_PATRULLA_IZQUIERDA => Some(Movimiento::Patrulla(Direccion::Izquierda)),
+            MOVIMIENTO_PATRULLA_VERTICAL => Some(Movimiento::Patrulla(Direccion::Abajo)),
+            MOVIMIENTO_PATRULLA_ARRIBA => Some(Movimiento::Patrulla(Direccion::Arriba)),
+            MOVIMIENTO_PERSEGUIDOR => Some(Movimiento::Perseguidor),
+            MOVIMIENTO_HUIDIZO => Some(Movimiento::Huidizo),
+            _ => None,
+        }
+    }
+
+    /// Devuelve el sufijo con el que se escribe el movimiento en los mapas, o None si el enemigo esta quieto.
+    /// Es la inversa de desde_char: las patrullas indican tambien el sentido en el que se estan moviendo.
+    pub fn char_movimiento(&self) -> Option<char> {
+        match self {
+            Movimiento::Quieto => None,
+            Movimiento::Aleatorio => Some(MOVIMIENTO_ALEATORIO),
+            Movimiento::Patrulla(Direccion::Derecha) => Some(MOVIMIENTO_PATRULLA_HORIZONTAL),
+            Movimiento::Patrulla(Direccion::Izquierda) => Some(MOVIMIENTO_PATRULLA_IZQUIERDA),
+            Movimiento::Patrulla(Direccion::Abajo) => Some(MOVIMIENTO_PATRULLA_VERTICAL),
+            Movimiento::Patrulla(Direccion::Arriba) => Some(MOVIMIENTO_PATRULLA_ARRIBA),
+            Movimiento::Perseguidor => Some(MOVIMIENTO_PERSEGUIDOR),
+            Movimiento::Huidizo => Some(MOVIMIENTO_HUIDIZO),
+        }
+    }
+}
@@ -1,16 +1,25 @@
+use super::premio::Premio;
+
 /// Representa un obstaculo en el mapa.
 /// Si pasable=true, el obstaculo va a poder ser sorteado por bombas especiales, caso contrario no.
+/// Una piedra puede esconder un premio que aparece cuando se la destruye.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Obstaculo {
     pub x: usize,
     pub y: usize,
     pub pasable: bool,
+    pub premio: Option<Premio>,
 }
 
 impl Obstaculo {
-    /// Crea un obstaculo.
+    /// Crea un obstaculo sin premio.
     /// Si pasable=true, el obstaculo va a poder ser sorteado por bombas especiales, caso contrario no.
     pub fn crear(x: usize, y: usize, pasable: bool) -> Obstaculo {
-        Obstaculo { x, y, pasable }
+        Obstaculo {
+            x,
+            y,
+            pasable,
+            premio: None,
+        }
     }
 }
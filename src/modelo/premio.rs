@@ -0,0 +1,35 @@
+use super::constantes::{PREMIO_BOMBA_ESPECIAL, PREMIO_BOMBA_EXTRA, PREMIO_MAS_RADIO};
+
+/// Mejora para el jugador escondida en una piedra. Aparece en el mapa cuando una explosion destruye la piedra,
+/// y el jugador la recoge al pasar por su casilla.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Premio {
+    /// El jugador puede tener una bomba mas puesta a la vez.
+    BombaExtra,
+    /// Las bombas del jugador tienen un radio mas.
+    MasRadio,
+    /// Las bombas del jugador pasan a ser especiales.
+    BombaEspecial,
+}
+
+impl Premio {
+    /// Devuelve el premio que corresponde al caracter usado en los mapas.
+    /// b: BombaExtra, r: MasRadio, s: BombaEspecial. Cualquier otro caracter devuelve None.
+    pub fn desde_char(c: char) -> Option<Premio> {
+        match c {
+            PREMIO_BOMBA_EXTRA => Some(Premio::BombaExtra),
+            PREMIO_MAS_RADIO => Some(Premio::MasRadio),
+            PREMIO_BOMBA_ESPECIAL => Some(Premio::BombaEspecial),
+            _ => None,
+        }
+    }
+
+    /// Devuelve el caracter con el que se escribe el premio en los mapas. Es la inversa de desde_char.
+    pub fn a_char(&self) -> char {
+        match self {
+            Premio::BombaExtra => PREMIO_BOMBA_EXTRA,
+            Premio::MasRadio => PREMIO_MAS_RADIO,
+            Premio::BombaEspecial => PREMIO_BOMBA_ESPECIAL,
+        }
+    }
+}
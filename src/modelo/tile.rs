@@ -1,4 +1,7 @@
-use super::{bomba::Bomba, desvio::Desvio, enemigo::Enemigo, obstaculo::Obstaculo};
+use super::{
+    bomba::Bomba, desvio::Desvio, enemigo::Enemigo, jugador::Jugador, obstaculo::Obstaculo,
+    premio::Premio,
+};
 
 /// Representa un tile del mapa.
 /// Agrupa todos los tipos posibles que pueden aparecer en el juego.
@@ -10,5 +13,8 @@ pub enum Tile {
     Desvio(Desvio),
     Piedra(Obstaculo),
     Pared(Obstaculo),
+    Jugador(Jugador),
+    /// Premio que quedo a la vista al destruir una piedra.
+    Premio(Premio),
     Vacio,
 }
@@ -0,0 +1,735 @@
+use crate::azar::{Azar, SEMILLA_POR_DEFECTO};
+use crate::ia::{self, Entorno};
+use crate::modelo::{
+    coordenada::Coordenada, direccion::Direccion, evento::Evento, jugador::Jugador, mapa::Mapa,
+    movimiento::Movimiento, premio::Premio, tile::Tile,
+};
+use crate::turno;
+use std::collections::HashSet;
+
+/// Que le pasa a una bomba alcanzada por la explosion de otra durante una partida.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModoCadena {
+    /// Detona en el mismo momento, como en turno::jugar_turno.
+    Instantanea,
+    /// No detona: su mecha pasa a ser la indicada, salvo que ya fuera mas corta. Si no tenia mecha, se enciende.
+    /// Con 0, la bomba explota en el mismo tick, despues de las que ya estaban por explotar.
+    AcortarMecha(u32),
+}
+
+/// Simulacion por turnos de un mapa: un reloj que avanza de a un tick y hace explotar las bombas cuya mecha se consume.
+/// Cada explosion se resuelve con las primitivas de turno.
+/// Si el mapa tiene un jugador, se puede mover y poner bombas entre tick y tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partida {
+    pub mapa: Mapa,
+    /// Cantidad de ticks que pasaron desde que empezo la partida.
+    pub tick: u32,
+    pub modo_cadena: ModoCadena,
+    /// El jugador no ocupa un tile de la grilla: su posicion es la que indica, y puede compartir la casilla con la bomba que acaba de poner.
+    pub jugador: Option<Jugador>,
+    /// Posiciones de las bombas puestas por el jugador que todavia no explotaron.
+    pub bombas_del_jugador: Vec<Coordenada>,
+    /// Generador que usan los enemigos que se mueven al azar.
+    pub azar: Azar,
+    /// Si es true, las explosiones destruyen las piedras que alcanzan y dejan a la vista sus premios.
+    /// La explosion de una bomba normal se detiene en la piedra; la de una especial destruye todas las que atraviesa.
+    /// Por defecto es false, y las piedras se comportan como en turno.
+    pub piedras_destructibles: bool,
+}
+
+impl Partida {
+    /// Crea una partida en el tick 0 con la semilla por defecto.
+    /// Si el mapa tiene un jugador, lo saca de la grilla y deja su casilla vacia.
+    /// Si tiene mas de uno, solo se controla el primero recorriendo el mapa por filas; el resto queda en la grilla como obstaculo.
+    pub fn crear(mapa: Mapa, modo_cadena: ModoCadena) -> Partida {
+        Partida::crear_con_semilla(mapa, modo_cadena, SEMILLA_POR_DEFECTO)
+    }
+
+    /// Crea una partida cuyos enemigos aleatorios usan la semilla dada. Dos partidas con la misma semilla y las mismas acciones terminan igual.
+    pub fn crear_con_semilla(mut mapa: Mapa, modo_cadena: ModoCadena, semilla: u64) -> Partida {
+        let jugador = mapa.tiles.iter().flatten().find_map(|tile| match tile {
+            Tile::Jugador(jugador) => Some(jugador.clone()),
+            _ => None,
+        });
+        if let Some(jugador) = &jugador {
+            mapa.destruir_tile(jugador.x, jugador.y);
+        }
+        Partida {
+            mapa,
+            tick: 0,
+            modo_cadena,
+            jugador,
+            bombas_del_jugador: Vec::new(),
+            azar: Azar::crear(semilla),
+            piedras_destructibles: false,
+        }
+    }
+
+    /// Mueve al jugador una casilla en la direccion dada, sin avanzar el reloj.
+    /// Solo puede moverse a casillas transitables: no atraviesa paredes, piedras, bombas ni enemigos.
+    /// Si la casilla tiene un premio, lo recoge y lo devuelve.
+    /// Si no hay jugador, fue eliminado o el movimiento esta bloqueado, devuelve un error y no lo mueve.
+    pub fn mover_jugador(&mut self, direccion: Direccion) -> Result<Option<Premio>, &'static str> {
+        let jugador = self.jugador_vivo()?;
+        let destino = self
+            .mapa
+            .vecina(jugador.x, jugador.y, direccion)
+            .filter(|destino| self.mapa.es_transitable(destino.x, destino.y))
+            .ok_or("El jugador no puede moverse en esa direccion")?;
+        let premio = match self.mapa.obtener_tile(destino.x, destino.y) {
+            Some(Tile::Premio(premio)) => Some(*premio),
+            _ => None,
+        };
+        if let Some(jugador) = self.jugador.as_mut() {
+            jugador.x = destino.x;
+            jugador.y = destino.y;
+            if let Some(premio) = premio {
+                jugador.recoger(premio);
+                self.mapa.destruir_tile(destino.x, destino.y);
+            }
+        }
+        Ok(premio)
+    }
+
+    /// Pone una bomba del jugador en su casilla, con su radio y la mecha de las bombas del jugador.
+    /// La bomba es especial si el jugador recogio ese premio.
+    /// Devuelve un error si no hay jugador, fue eliminado, ya tiene puestas tantas bombas como su capacidad
+    /// o su casilla no esta vacia, por ejemplo porque esta sobre un desvio.
+    pub fn poner_bomba(&mut self) -> Result<(), &'static str> {
+        let jugador = self.jugador_vivo()?;
+        if self.bombas_disponibles() == 0 {
+            return Err("El jugador no tiene bombas disponibles");
+        }
+        let bomba = jugador.crear_bomba();
+        match self.mapa.obtener_tile_mut(bomba.x, bomba.y) {
+            Some(tile) if *tile == Tile::Vacio => {
+                self.bombas_del_jugador.push(bomba.coordenadas());
+                *tile = if bomba.especial {
+                    Tile::BombaEspecial(bomba)
+                } else {
+                    Tile::BombaNormal(bomba)
+                };
+                Ok(())
+            }
+            _ => Err("Solo se puede poner una bomba en una casilla vacia"),
+        }
+    }
+
+    /// Devuelve cuantas bombas mas puede poner el jugador, o 0 si no hay jugador.
+    pub fn bombas_disponibles(&self) -> u32 {
+        match &self.jugador {
+            Some(jugador) => jugador
+                .capacidad
+                .saturating_sub(self.bombas_del_jugador.len() as u32),
+            None => 0,
+        }
+    }
+
+    /// Devuelve true si no quedan enemigos o si una explosion alcanzo al jugador.
+    pub fn terminada(&self) -> bool {
+        self.enemigos_restantes() == 0 || self.jugador.as_ref().is_some_and(|j| !j.vivo)
+    }
+
+    /// Detona la bomba en la posicion (x_pos, y_pos) sin esperar a su mecha, sin avanzar el reloj.
+    /// Si no hay bomba en la posicion, devuelve un error y no modifica el mapa.
+    pub fn detonar(&mut self, x_pos: usize, y_pos: usize) -> Result<Vec<Evento>, &'static str> {
+        let mut eventos = self.explotar(x_pos, y_pos)?;
+        eventos.append(&mut self.explotar_mechas_consumidas());
+        Ok(eventos)
+    }
+
+    /// Avanza el reloj un tick: mueve a los enemigos, descuenta uno a la mecha de cada bomba encendida
+    /// y hace explotar, recorriendo el mapa por filas, las que se consumieron.
+    /// Devuelve los eventos ocurridos durante el tick.
+    pub fn avanzar(&mut self) -> Vec<Evento> {
+        self.tick += 1;
+        let mut eventos = self.mover_enemigos();
+        for tile in self.mapa.tiles.iter_mut().flatten() {
+            if let Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) = tile {
+                if let Some(mecha) = bomba.mecha.as_mut() {
+                    *mecha = mecha.saturating_sub(1);
+                }
+            }
+        }
+        eventos.append(&mut self.explotar_mechas_consumidas());
+        eventos
+    }
+
+    /// Avanza el reloj hasta que no quede ninguna bomba encendida y devuelve todos los eventos ocurridos.
+    /// Siempre termina: cada tick consume las mechas, y las explosiones solo pueden acortarlas.
+    pub fn avanzar_hasta_apagarse(&mut self) -> Vec<Evento> {
+        let mut eventos = Vec::new();
+        while self.bombas_encendidas() > 0 {
+            eventos.append(&mut self.avanzar());
+        }
+        eventos
+    }
+
+    /// Devuelve la cantidad de bombas con mecha que quedan en el mapa.
+    pub fn bombas_encendidas(&self) -> usize {
+        self.mapa
+            .tiles
+            .iter()
+            .flatten()
+            .filter(|tile| {
+                matches!(
+                    tile,
+                    Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) if bomba.mecha.is_some()
+                )
+            })
+            .count()
+    }
+
+    /// Devuelve la cantidad de enemigos que quedan en el mapa.
+    pub fn enemigos_restantes(&self) -> usize {
+        self.mapa
+            .tiles
+            .iter()
+            .flatten()
+            .filter(|tile| matches!(tile, Tile::Enemigo(_)))
+            .count()
+    }
+
+    /// Devuelve el jugador, o un error si la partida no tiene jugador o si fue eliminado.
+    fn jugador_vivo(&self) -> Result<&Jugador, &'static str> {
+        match &self.jugador {
+            Some(jugador) if jugador.vivo => Ok(jugador),
+            Some(_) => Err("El jugador fue eliminado"),
+            None => Err("La partida no tiene jugador"),
+        }
+    }
+
+    /// Mueve una vez, recorriendo el mapa por filas, a cada enemigo que tiene un comportamiento.
+    /// Un enemigo solo se mueve a una casilla libre; si llega a la del jugador, lo elimina.
+    fn mover_enemigos(&mut self) -> Vec<Evento> {
+        let mut eventos = Vec::new();
+        let enemigos: Vec<Coordenada> = self
+            .mapa
+            .tiles
+            .iter()
+            .flatten()
+            .filter_map(|tile| match tile {
+                Tile::Enemigo(enemigo) if enemigo.movimiento != Movimiento::Quieto => {
+                    Some(enemigo.coordenadas())
+                }
+                _ => None,
+            })
+            .collect();
+        // Un enemigo que llega a una casilla que todavia no se recorrio no se vuelve a mover en el mismo tick.
+        let mut movidos: HashSet<Coordenada> = HashSet::new();
+        for origen in enemigos {
+            if movidos.contains(&origen) {
+                continue;
+            }
+            let enemigo = match self.mapa.obtener_tile(origen.x, origen.y) {
+                Some(Tile::Enemigo(enemigo)) => enemigo,
+                _ => continue,
+            };
+            let comportamiento = match ia::comportamiento(&enemigo.movimiento) {
+                Some(comportamiento) => comportamiento,
+                None => continue,
+            };
+            let jugador = self
+                .jugador
+                .as_ref()
+                .filter(|jugador| jugador.vivo)
+                .map(|jugador| jugador.coordenadas());
+            let mut entorno = Entorno {
+                mapa: &self.mapa,
+                jugador,
+                azar: &mut self.azar,
+            };
+            let paso = comportamiento
+                .elegir(enemigo, &mut entorno)
+                .and_then(|direccion| {
+                    self.mapa
+                        .vecina(origen.x, origen.y, direccion)
+                        .filter(|destino| ia::libre(&self.mapa, destino))
+                        .map(|destino| (direccion, destino))
+                });
+            let (direccion, destino) = match paso {
+                Some(paso) => paso,
+                None => continue,
+            };
+
+            let mut tile = std::mem::replace(&mut self.mapa.tiles[origen.y][origen.x], Tile::Vacio);
+            if let Tile::Enemigo(enemigo) = &mut tile {
+                enemigo.x = destino.x;
+                enemigo.y = destino.y;
+                if let Movimiento::Patrulla(sentido) = &mut enemigo.movimiento {
+                    *sentido = direccion;
+                }
+            }
+            self.mapa.tiles[destino.y][destino.x] = tile;
+            movidos.insert(destino);
+            eventos.push(Evento::EnemigoMovido { origen, destino });
+
+            if jugador == Some(destino) {
+                if let Some(jugador) = self.jugador.as_mut() {
+                    jugador.vivo = false;
+                }
+                eventos.push(Evento::JugadorAtrapado { enemigo: destino });
+            }
+        }
+        eventos
+    }
+
+    /// Devuelve la primera bomba, recorriendo el mapa por filas, cuya mecha se consumio.
+    fn mecha_consumida(&self) -> Option<Coordenada> {
+        self.mapa
+            .tiles
+            .iter()
+            .flatten()
+            .find_map(|tile| match tile {
+                Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) if bomba.mecha == Some(0) => {
+                    Some(bomba.coordenadas())
+                }
+                _ => None,
+            })
+    }
+
+    /// Hace explotar las bombas con la mecha consumida hasta que no quede ninguna,
+    /// incluidas las que se consumen por explosiones de este mismo tick.
+    /// Si una explosion falla, se detiene: la bomba seguiria con la mecha consumida y se la volveria a encontrar siempre.
+    fn explotar_mechas_consumidas(&mut self) -> Vec<Evento> {
+        let mut eventos = Vec::new();
+        while let Some(bomba) = self.mecha_consumida() {
+            match self.explotar(bomba.x, bomba.y) {
+                Ok(mut nuevos) => eventos.append(&mut nuevos),
+                Err(_) => break,
+            }
+        }
+        eventos
+    }
+
+    /// Hace explotar la bomba en la posicion resolviendo las bombas alcanzadas segun el modo de cadena.
+    /// Si alguna explosion alcanza al jugador, lo elimina.
+    fn explotar(&mut self, x_pos: usize, y_pos: usize) -> Result<Vec<Evento>, &'static str> {
+        let antes = self.piedras_destructibles.then(|| self.mapa.clone());
+        let mut eventos = match self.modo_cadena {
+            ModoCadena::Instantanea => {
+                turno::jugar_turno_con_eventos(&mut self.mapa, x_pos, y_pos)?
+            }
+            ModoCadena::AcortarMecha(mecha) => self.explotar_acortando(x_pos, y_pos, mecha)?,
+        };
+        if let Some(antes) = antes {
+            self.romper_piedras(&antes, &mut eventos);
+        }
+        self.alcanzar_jugador(&mut eventos);
+        let mapa = &self.mapa;
+        self.bombas_del_jugador.retain(|bomba| {
+            matches!(
+                mapa.obtener_tile(bomba.x, bomba.y),
+                Some(Tile::BombaNormal(_) | Tile::BombaEspecial(_))
+            )
+        });
+        Ok(eventos)
+    }
+
+    /// Destruye las piedras que alcanzaron las bombas que explotaron, dejando a la vista sus premios.
+    /// El alcance se calcula sobre el mapa de antes de la explosion: las piedras se destruyen al final,
+    /// por lo que ninguna bomba de la misma reaccion en cadena pasa por una piedra recien destruida.
+    fn romper_piedras(&mut self, antes: &Mapa, eventos: &mut Vec<Evento>) {
+        let detonadas: Vec<Coordenada> = eventos
+            .iter()
+            .filter_map(|evento| match evento {
+                Evento::BombaDetonada { bomba, .. } => Some(*bomba),
+                _ => None,
+            })
+            .collect();
+        for origen in detonadas {
+            let bomba = match antes.obtener_tile(origen.x, origen.y) {
+                Some(Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba)) => bomba.clone(),
+                _ => continue,
+            };
+            for alcanzada in turno::buscar_piedras(antes, origen.x, origen.y, bomba) {
+                let piedra = alcanzada.coordenada;
+                let premio = match self.mapa.obtener_tile(piedra.x, piedra.y) {
+                    Some(Tile::Piedra(obstaculo)) => obstaculo.premio,
+                    _ => continue,
+                };
+                if let Some(tile) = self.mapa.obtener_tile_mut(piedra.x, piedra.y) {
+                    *tile = premio.map_or(Tile::Vacio, Tile::Premio);
+                }
+                eventos.push(Evento::PiedraDestruida {
+                    bomba: origen,
+                    piedra,
+                    premio,
+                });
+            }
+        }
+    }
+
+    /// Elimina al jugador si su casilla esta entre las alcanzadas por los eventos, incluida la de una bomba que exploto.
+    fn alcanzar_jugador(&mut self, eventos: &mut Vec<Evento>) {
+        let jugador = match self.jugador.as_mut() {
+            Some(jugador) if jugador.vivo => jugador,
+            _ => return,
+        };
+        let posicion = jugador.coordenadas();
+        let bomba = eventos.iter().find_map(|evento| match evento {
+            Evento::BombaDetonada { bomba, .. } if *bomba == posicion => Some(*bomba),
+            Evento::TileAlcanzado { bomba, tile, .. } if *tile == posicion => Some(*bomba),
+            _ => None,
+        });
+        if let Some(bomba) = bomba {
+            jugador.vivo = false;
+            eventos.push(Evento::JugadorAlcanzado {
+                bomba,
+                jugador: posicion,
+            });
+        }
+    }
+
+    /// Hace explotar la bomba en la posicion sin detonar las que alcanza: a cada una le acorta la mecha a mecha_nueva.
+    fn explotar_acortando(
+        &mut self,
+        x_pos: usize,
+        y_pos: usize,
+        mecha_nueva: u32,
+    ) -> Result<Vec<Evento>, &'static str> {
+        let mut eventos = turno::detonar_sin_cadena(&mut self.mapa, x_pos, y_pos)?;
+        let alcanzadas: Vec<(Coordenada, Coordenada)> = eventos
+            .iter()
+            .filter_map(|evento| match evento {
+                Evento::TileAlcanzado { bomba, tile, .. } => Some((*bomba, *tile)),
+                _ => None,
+            })
+            .collect();
+        for (origen, tile) in alcanzadas {
+            if let Some(Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba)) =
+                self.mapa.obtener_tile_mut(tile.x, tile.y)
+            {
+                let acorta = match bomba.mecha {
+                    Some(mecha) => mecha > mecha_nueva,
+                    None => true,
+                };
+                if acorta {
+                    bomba.mecha = Some(mecha_nueva);
+                    eventos.push(Evento::MechaAcortada {
+                        origen,
+                        bomba: tile,
+                        mecha: mecha_nueva,
+                    });
+                }
+            }
+        }
+        Ok(eventos)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn partida(mapa: &str, modo_cadena: ModoCadena) -> Partida {
+        Partida::crear(mapa.parse().unwrap(), modo_cadena)
+    }
+
+    #[test]
+    fn test_bomba_explota_cuando_se_consume_la_mecha() {
+        let mut partida = partida("F1 B1m2 _", ModoCadena::Instantanea);
+        assert_eq!(partida.avanzar(), vec![]);
+        assert_eq!(partida.mapa.to_string(), "F1 B1m1 _\n");
+        let eventos = partida.avanzar();
+        assert_eq!(partida.tick, 2);
+        assert!(eventos.contains(&Evento::EnemigoEliminado {
+            bomba: Coordenada { x: 1, y: 0 },
+            enemigo: Coordenada { x: 0, y: 0 }
+        }));
+        assert_eq!(partida.mapa.to_string(), "_ _ _\n");
+        assert_eq!(partida.bombas_encendidas(), 0);
+        assert_eq!(partida.enemigos_restantes(), 0);
+    }
+
+    #[test]
+    fn test_bombas_sin_mecha_no_explotan_solas() {
+        let mut partida = partida("F1 B1 _", ModoCadena::Instantanea);
+        assert_eq!(partida.avanzar_hasta_apagarse(), vec![]);
+        assert_eq!(partida.tick, 0);
+        for _ in 0..5 {
+            partida.avanzar();
+        }
+        assert_eq!(partida.enemigos_restantes(), 1);
+        assert!(partida.detonar(1, 0).is_ok());
+        assert_eq!(partida.enemigos_restantes(), 0);
+        assert!(partida.detonar(1, 0).is_err());
+    }
+
+    #[test]
+    fn test_cadena_instantanea() {
+        let mut partida = partida("B1m1 B1m9 F1", ModoCadena::Instantanea);
+        let eventos = partida.avanzar();
+        assert!(eventos.contains(&Evento::ReaccionEnCadena {
+            origen: Coordenada { x: 0, y: 0 },
+            bomba: Coordenada { x: 1, y: 0 }
+        }));
+        assert_eq!(partida.mapa.to_string(), "_ _ _\n");
+    }
+
+    #[test]
+    fn test_cadena_acortando_mecha() {
+        let mut partida = partida("B1m1 B1 F1", ModoCadena::AcortarMecha(2));
+        let eventos = partida.avanzar();
+        assert!(eventos.contains(&Evento::MechaAcortada {
+            origen: Coordenada { x: 0, y: 0 },
+            bomba: Coordenada { x: 1, y: 0 },
+            mecha: 2
+        }));
+        assert_eq!(partida.mapa.to_string(), "_ B1m2 F1\n");
+        assert_eq!(partida.avanzar(), vec![]);
+        partida.avanzar();
+        assert_eq!(partida.tick, 3);
+        assert_eq!(partida.mapa.to_string(), "_ _ _\n");
+    }
+
+    #[test]
+    fn test_acortar_no_alarga_una_mecha_mas_corta() {
+        let mut partida = partida("B1m1 B1m2 F2", ModoCadena::AcortarMecha(5));
+        let eventos = partida.avanzar();
+        assert!(!eventos
+            .iter()
+            .any(|evento| matches!(evento, Evento::MechaAcortada { .. })));
+        assert_eq!(partida.mapa.to_string(), "_ B1m1 F2\n");
+    }
+
+    #[test]
+    fn test_mecha_cero_explota_en_el_mismo_tick() {
+        let mut partida = partida("B1m1 B1 B1 F1", ModoCadena::AcortarMecha(0));
+        partida.avanzar();
+        assert_eq!(partida.tick, 1);
+        assert_eq!(partida.mapa.to_string(), "_ _ _ _\n");
+    }
+
+    #[test]
+    fn test_avanzar_hasta_apagarse() {
+        let mut partida = partida("B1m3 _ F2\n_ _ B1m5", ModoCadena::Instantanea);
+        partida.avanzar_hasta_apagarse();
+        assert_eq!(partida.tick, 5);
+        assert_eq!(partida.bombas_encendidas(), 0);
+        assert_eq!(partida.mapa.to_string(), "_ _ F1i2,1\n_ _ _\n");
+    }
+
+    #[test]
+    fn test_crear_saca_al_jugador_de_la_grilla() {
+        let partida = partida("_ Jc2r3 F1", ModoCadena::Instantanea);
+        assert_eq!(partida.mapa.to_string(), "_ _ F1\n");
+        let jugador = partida.jugador.as_ref().unwrap();
+        assert_eq!(jugador.coordenadas(), Coordenada { x: 1, y: 0 });
+        assert_eq!((jugador.capacidad, jugador.radio), (2, 3));
+        assert_eq!(partida.bombas_disponibles(), 2);
+
+        let mut sin_jugador = Partida::crear("F1 B1".parse().unwrap(), ModoCadena::Instantanea);
+        assert_eq!(sin_jugador.bombas_disponibles(), 0);
+        assert!(sin_jugador.mover_jugador(Direccion::Abajo).is_err());
+        assert!(sin_jugador.poner_bomba().is_err());
+    }
+
+    #[test]
+    fn test_mover_jugador() {
+        let mut partida = partida("_ W R\nJ B1 F1\nDU _ _", ModoCadena::Instantanea);
+        let posicion = |partida: &Partida| partida.jugador.as_ref().unwrap().coordenadas();
+        assert!(partida.mover_jugador(Direccion::Arriba).is_ok());
+        assert_eq!(posicion(&partida), Coordenada { x: 0, y: 0 });
+        assert!(partida.mover_jugador(Direccion::Derecha).is_err());
+        assert!(partida.mover_jugador(Direccion::Arriba).is_err());
+        partida.mover_jugador(Direccion::Abajo).unwrap();
+        assert!(partida.mover_jugador(Direccion::Derecha).is_err());
+        partida.mover_jugador(Direccion::Abajo).unwrap();
+        assert_eq!(posicion(&partida), Coordenada { x: 0, y: 2 });
+        assert!(partida.mover_jugador(Direccion::Izquierda).is_err());
+        assert!(partida.poner_bomba().is_err());
+        partida.mover_jugador(Direccion::Derecha).unwrap();
+        partida.mover_jugador(Direccion::Derecha).unwrap();
+        assert!(partida.mover_jugador(Direccion::Arriba).is_err());
+        assert_eq!(posicion(&partida), Coordenada { x: 2, y: 2 });
+        assert_eq!(partida.tick, 0);
+    }
+
+    #[test]
+    fn test_poner_bomba_respeta_la_capacidad() {
+        let mut partida = partida("J _ _ F1", ModoCadena::Instantanea);
+        partida.poner_bomba().unwrap();
+        assert_eq!(partida.mapa.to_string(), "B1m3 _ _ F1\n");
+        assert_eq!(partida.bombas_disponibles(), 0);
+        assert_eq!(
+            partida.poner_bomba(),
+            Err("El jugador no tiene bombas disponibles")
+        );
+        partida.mover_jugador(Direccion::Derecha).unwrap();
+        partida.mover_jugador(Direccion::Derecha).unwrap();
+        assert!(partida.poner_bomba().is_err());
+        let eventos = partida.avanzar_hasta_apagarse();
+        assert_eq!(partida.tick, 3);
+        assert!(eventos.contains(&Evento::BombaDetonada {
+            bomba: Coordenada { x: 0, y: 0 },
+            disparada_por: None
+        }));
+        assert!(partida.jugador.as_ref().unwrap().vivo);
+        assert_eq!(partida.bombas_disponibles(), 1);
+        assert!(partida.poner_bomba().is_ok());
+    }
+
+    #[test]
+    fn test_explosion_alcanza_al_jugador() {
+        let mut partida = partida("J _ F1", ModoCadena::Instantanea);
+        partida.poner_bomba().unwrap();
+        partida.mover_jugador(Direccion::Derecha).unwrap();
+        let eventos = partida.avanzar_hasta_apagarse();
+        assert!(eventos.contains(&Evento::JugadorAlcanzado {
+            bomba: Coordenada { x: 0, y: 0 },
+            jugador: Coordenada { x: 1, y: 0 }
+        }));
+        assert!(!partida.jugador.as_ref().unwrap().vivo);
+        assert_eq!(partida.enemigos_restantes(), 1);
+        assert!(partida.terminada());
+        assert_eq!(
+            partida.mover_jugador(Direccion::Izquierda),
+            Err("El jugador fue eliminado")
+        );
+
+        let mut solo = Partida::crear("J".parse().unwrap(), ModoCadena::Instantanea);
+        solo.poner_bomba().unwrap();
+        solo.avanzar_hasta_apagarse();
+        assert!(!solo.jugador.unwrap().vivo);
+    }
+
+    #[test]
+    fn test_ronda_ganada() {
+        let mut partida = partida("Jr2 _ F1\n_ _ _", ModoCadena::AcortarMecha(1));
+        partida.poner_bomba().unwrap();
+        partida.mover_jugador(Direccion::Abajo).unwrap();
+        assert!(!partida.terminada());
+        partida.mover_jugador(Direccion::Derecha).unwrap();
+        partida.avanzar_hasta_apagarse();
+        assert_eq!(partida.enemigos_restantes(), 0);
+        assert!(partida.jugador.as_ref().unwrap().vivo);
+        assert!(partida.terminada());
+    }
+
+    #[test]
+    fn test_patrulla_va_y_viene() {
+        let mut partida = partida("F1h _ _", ModoCadena::Instantanea);
+        let eventos = partida.avanzar();
+        assert_eq!(
+            eventos,
+            vec![Evento::EnemigoMovido {
+                origen: Coordenada { x: 0, y: 0 },
+                destino: Coordenada { x: 1, y: 0 }
+            }]
+        );
+        partida.avanzar();
+        assert_eq!(partida.mapa.to_string(), "_ _ F1h\n");
+        partida.avanzar();
+        assert_eq!(partida.mapa.to_string(), "_ F1H _\n");
+        assert!(matches!(
+            partida.mapa.obtener_tile(1, 0),
+            Some(Tile::Enemigo(enemigo)) if enemigo.movimiento == Movimiento::Patrulla(Direccion::Izquierda)
+        ));
+    }
+
+    #[test]
+    fn test_cada_enemigo_se_mueve_una_vez_por_tick() {
+        let mut partida = partida("F1h _ _ _\nF1v _ _ _\n_ _ _ _", ModoCadena::Instantanea);
+        partida.avanzar();
+        assert_eq!(partida.mapa.to_string(), "_ F1h _ _\n_ _ _ _\nF1v _ _ _\n");
+    }
+
+    #[test]
+    fn test_perseguidor_atrapa_al_jugador() {
+        let mut partida = partida("F1p W _\n_ _ J", ModoCadena::Instantanea);
+        partida.avanzar();
+        assert!(!partida.terminada());
+        partida.avanzar();
+        let eventos = partida.avanzar();
+        assert_eq!(
+            eventos.last(),
+            Some(&Evento::JugadorAtrapado {
+                enemigo: Coordenada { x: 2, y: 1 }
+            })
+        );
+        assert!(partida.terminada());
+        assert_eq!(partida.avanzar(), vec![]);
+    }
+
+    #[test]
+    fn test_huidizo_escapa_de_la_bomba() {
+        let mut partida = partida("B1m2 F1e _", ModoCadena::Instantanea);
+        partida.avanzar_hasta_apagarse();
+        assert_eq!(partida.mapa.to_string(), "_ _ F1e\n");
+
+        let mut quieto = Partida::crear("B1m2 F1 _".parse().unwrap(), ModoCadena::Instantanea);
+        quieto.avanzar_hasta_apagarse();
+        assert_eq!(quieto.enemigos_restantes(), 0);
+    }
+
+    #[test]
+    fn test_aleatorio_depende_solo_de_la_semilla() {
+        let mapa: Mapa = "_ _ _ _\n_ F1a _ _\n_ _ W _\n_ _ _ F2a".parse().unwrap();
+        let jugar = |semilla: u64| {
+            let mut partida =
+                Partida::crear_con_semilla(mapa.clone(), ModoCadena::Instantanea, semilla);
+            for _ in 0..20 {
+                partida.avanzar();
+            }
+            partida.mapa.to_string()
+        };
+        assert_eq!(jugar(42), jugar(42));
+        assert_eq!(
+            Partida::crear(mapa.clone(), ModoCadena::Instantanea).azar,
+            Azar::crear(SEMILLA_POR_DEFECTO)
+        );
+        let final_42 = jugar(42);
+        assert_eq!(final_42.matches("F1a").count(), 1);
+        assert_eq!(final_42.matches("F2a").count(), 1);
+    }
+
+    #[test]
+    fn test_piedras_indestructibles_por_defecto() {
+        let mut partida = partida("B2m1 Rb F1", ModoCadena::Instantanea);
+        partida.avanzar();
+        assert_eq!(partida.mapa.to_string(), "_ Rb F1\n");
+    }
+
+    #[test]
+    fn test_explosion_destruye_la_piedra_y_se_detiene() {
+        let mut partida = partida("B2m1 Rb F1\nR _ _\n_ _ _", ModoCadena::Instantanea);
+        partida.piedras_destructibles = true;
+        let eventos = partida.avanzar();
+        assert!(eventos.contains(&Evento::PiedraDestruida {
+            bomba: Coordenada { x: 0, y: 0 },
+            piedra: Coordenada { x: 1, y: 0 },
+            premio: Some(Premio::BombaExtra)
+        }));
+        assert_eq!(partida.mapa.to_string(), "_ Pb F1\n_ _ _\n_ _ _\n");
+    }
+
+    #[test]
+    fn test_bomba_especial_destruye_todas_las_piedras() {
+        let mut partida = partida("S3m1 R Rs F1", ModoCadena::AcortarMecha(1));
+        partida.piedras_destructibles = true;
+        partida.avanzar();
+        assert_eq!(partida.mapa.to_string(), "_ _ Ps _\n");
+    }
+
+    #[test]
+    fn test_jugador_recoge_premios() {
+        let mut partida = partida("J Rr\n_ Ps", ModoCadena::Instantanea);
+        partida.piedras_destructibles = true;
+        partida.poner_bomba().unwrap();
+        assert_eq!(partida.mover_jugador(Direccion::Abajo), Ok(None));
+        assert_eq!(
+            partida.mover_jugador(Direccion::Derecha),
+            Ok(Some(Premio::BombaEspecial))
+        );
+        partida.avanzar_hasta_apagarse();
+        assert_eq!(partida.mapa.to_string(), "_ Pr\n_ _\n");
+        assert_eq!(
+            partida.mover_jugador(Direccion::Arriba),
+            Ok(Some(Premio::MasRadio))
+        );
+        partida.poner_bomba().unwrap();
+        assert_eq!(partida.mapa.to_string(), "_ S2m3\n_ _\n");
+    }
+}
@@ -0,0 +1,138 @@
+use crate::modelo::{coordenada::Coordenada, mapa::Mapa, tile::Tile};
+use crate::turno;
+use std::collections::{HashSet, VecDeque};
+
+/// Resume la parte del mapa que puede cambiar al detonar bombas: las bombas que quedan y el estado de cada enemigo.
+/// Dos mapas con el mismo estado van a tener el mismo resultado ante las mismas detonaciones.
+type Estado = (Vec<Coordenada>, Vec<(Coordenada, u32, Vec<(usize, usize)>)>);
+
+/// Devuelve el estado del mapa.
+fn estado(mapa: &Mapa) -> Estado {
+    let mut bombas = Vec::new();
+    let mut enemigos = Vec::new();
+    for tile in mapa.tiles.iter().flatten() {
+        match tile {
+            Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) => {
+                bombas.push(bomba.coordenadas())
+            }
+            Tile::Enemigo(enemigo) => {
+                let mut impactos: Vec<(usize, usize)> = enemigo
+                    .bombas_impactadas
+                    .iter()
+                    .map(|bomba| (bomba.x, bomba.y))
+                    .collect();
+                impactos.sort();
+                enemigos.push((enemigo.coordenadas(), enemigo.vida, impactos));
+            }
+            _ => continue,
+        }
+    }
+    (bombas, enemigos)
+}
+
+/// Devuelve true si no queda ningun enemigo en el mapa.
+fn sin_enemigos(mapa: &Mapa) -> bool {
+    !mapa
+        .tiles
+        .iter()
+        .flatten()
+        .any(|tile| matches!(tile, Tile::Enemigo(_)))
+}
+
+/// Devuelve las posiciones de las bombas que quedan en el mapa, recorriendolo por filas.
+fn bombas(mapa: &Mapa) -> Vec<Coordenada> {
+    estado(mapa).0
+}
+
+/// Busca la menor cantidad de detonaciones que eliminan a todos los enemigos del mapa.
+/// Devuelve las posiciones de las bombas a detonar, en el orden en el que hay que detonarlas con turno::jugar_turno.
+/// Si el mapa no tiene enemigos devuelve un vector vacio, y si es imposible eliminarlos a todos devuelve None.
+/// Recorre las secuencias de detonaciones a lo ancho, descartando las que llevan a un estado ya visitado.
+pub fn resolver(mapa: &Mapa) -> Option<Vec<Coordenada>> {
+    let mut pendientes = VecDeque::from([(mapa.clone(), Vec::new())]);
+    let mut visitados = HashSet::from([estado(mapa)]);
+
+    while let Some((actual, detonaciones)) = pendientes.pop_front() {
+        if sin_enemigos(&actual) {
+            return Some(detonaciones);
+        }
+        for bomba in bombas(&actual) {
+            let mut siguiente = actual.clone();
+            if turno::jugar_turno(&mut siguiente, bomba.x, bomba.y).is_err() {
+                continue;
+            }
+            if visitados.insert(estado(&siguiente)) {
+                let mut siguientes_detonaciones = detonaciones.clone();
+                siguientes_detonaciones.push(bomba);
+                pendientes.push_back((siguiente, siguientes_detonaciones));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bomberman_game::transformar_a_mapa;
+    use crate::modelo::{bomba::Bomba, enemigo::Enemigo, obstaculo::Obstaculo};
+
+    #[test]
+    fn test_resolver_sin_enemigos() {
+        let mapa = Mapa {
+            ancho: 2,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 1, false)),
+                Tile::Vacio,
+            ]],
+        };
+        assert_eq!(resolver(&mapa), Some(vec![]));
+    }
+
+    #[test]
+    fn test_resolver_reaccion_en_cadena() {
+        let mapa = transformar_a_mapa("mapas/mapa_3.txt").unwrap();
+        let detonaciones = resolver(&mapa).unwrap();
+        assert_eq!(detonaciones.len(), 1);
+
+        let mut jugado = mapa.clone();
+        for bomba in detonaciones {
+            turno::jugar_turno(&mut jugado, bomba.x, bomba.y).unwrap();
+        }
+        assert!(sin_enemigos(&jugado));
+    }
+
+    #[test]
+    fn test_resolver_necesita_varias_detonaciones() {
+        let mapa = Mapa {
+            ancho: 5,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 1, false)),
+                Tile::Enemigo(Enemigo::crear(1, 0, 2)),
+                Tile::BombaNormal(Bomba::crear(2, 0, 1, false)),
+                Tile::Pared(Obstaculo::crear(3, 0, true)),
+                Tile::BombaNormal(Bomba::crear(4, 0, 1, false)),
+            ]],
+        };
+        assert_eq!(
+            resolver(&mapa),
+            Some(vec![Coordenada { x: 0, y: 0 }, Coordenada { x: 2, y: 0 }])
+        );
+    }
+
+    #[test]
+    fn test_resolver_imposible() {
+        let mapa = Mapa {
+            ancho: 3,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 5, false)),
+                Tile::Pared(Obstaculo::crear(1, 0, true)),
+                Tile::Enemigo(Enemigo::crear(2, 0, 1)),
+            ]],
+        };
+        assert_eq!(resolver(&mapa), None);
+    }
+}
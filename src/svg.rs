@@ -0,0 +1,193 @@
+use crate::dibujo;
+use crate::modelo::{coordenada::Coordenada, evento::Evento, mapa::Mapa, tile::Tile};
+
+/// Lado de cada casilla en pixeles.
+const TAMANIO_CELDA: usize = 40;
+const COLOR_FONDO: &str = "#f4f1e8";
+const COLOR_GRILLA: &str = "#c8c2b4";
+const COLOR_PARED: &str = "#3b3b3b";
+const COLOR_PIEDRA: &str = "#8d6e4c";
+const COLOR_ENEMIGO: &str = "#c92a2a";
+const COLOR_BOMBA: &str = "#212529";
+const COLOR_BOMBA_ESPECIAL: &str = "#7048e8";
+const COLOR_DESVIO: &str = "#1098ad";
+const COLOR_JUGADOR: &str = "#2f9e44";
+const COLOR_PREMIO: &str = "#f59f00";
+const COLOR_EXPLOSION: &str = "#e8590c";
+
+/// Devuelve el centro de la casilla en pixeles.
+fn centro(coordenada: &Coordenada) -> (usize, usize) {
+    (
+        coordenada.x * TAMANIO_CELDA + TAMANIO_CELDA / 2,
+        coordenada.y * TAMANIO_CELDA + TAMANIO_CELDA / 2,
+    )
+}
+
+/// Dibuja un circulo con un texto centrado, usado para enemigos, bombas, el jugador y los premios.
+fn circulo_con_texto(x: usize, y: usize, color: &str, texto: &str) -> String {
+    format!(
+        "  <circle cx=\"{cx}\" cy=\"{cy}\" r=\"{r}\" fill=\"{color}\"/>\n  <text x=\"{cx}\" y=\"{cy}\" fill=\"white\" font-family=\"monospace\" font-size=\"13\" text-anchor=\"middle\" dominant-baseline=\"central\">{texto}</text>\n",
+        cx = x,
+        cy = y,
+        r = TAMANIO_CELDA * 2 / 5,
+    )
+}
+
+/// Dibuja el tile en la casilla dada. Las casillas vacias no dibujan nada.
+fn dibujar_tile(tile: &Tile, coordenada: &Coordenada) -> String {
+    let (cx, cy) = centro(coordenada);
+    let (x, y) = (coordenada.x * TAMANIO_CELDA, coordenada.y * TAMANIO_CELDA);
+    match tile {
+        Tile::Pared(_) => format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{t}\" height=\"{t}\" fill=\"{}\"/>\n",
+            x,
+            y,
+            COLOR_PARED,
+            t = TAMANIO_CELDA
+        ),
+        Tile::Piedra(_) => format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{t}\" height=\"{t}\" rx=\"8\" fill=\"{}\"/>\n",
+            x + 4,
+            y + 4,
+            COLOR_PIEDRA,
+            t = TAMANIO_CELDA - 8
+        ),
+        Tile::Enemigo(enemigo) => {
+            circulo_con_texto(cx, cy, COLOR_ENEMIGO, &enemigo.vida.to_string())
+        }
+        Tile::BombaNormal(_) => circulo_con_texto(cx, cy, COLOR_BOMBA, &tile.to_string()),
+        Tile::BombaEspecial(_) => {
+            circulo_con_texto(cx, cy, COLOR_BOMBA_ESPECIAL, &tile.to_string())
+        }
+        Tile::Desvio(desvio) => {
+            // Triangulo que apunta en la direccion del desvio: la punta sobre (dx, dy) y la base sobre la perpendicular.
+            let (dx, dy) = desvio.direccion.desplazamiento();
+            let (px, py) = (-dy, dx);
+            let (cx, cy) = (cx as i32, cy as i32);
+            format!(
+                "  <polygon points=\"{},{} {},{} {},{}\" fill=\"{}\"/>\n",
+                cx + 12 * dx,
+                cy + 12 * dy,
+                cx - 8 * dx + 10 * px,
+                cy - 8 * dy + 10 * py,
+                cx - 8 * dx - 10 * px,
+                cy - 8 * dy - 10 * py,
+                COLOR_DESVIO
+            )
+        }
+        Tile::Jugador(_) => circulo_con_texto(cx, cy, COLOR_JUGADOR, &tile.to_string()),
+        Tile::Premio(_) => circulo_con_texto(cx, cy, COLOR_PREMIO, &tile.to_string()),
+        Tile::Vacio => String::new(),
+    }
+}
+
+/// Dibuja el recorrido de las explosiones de los eventos: cada casilla alcanzada se sombrea,
+/// y se traza una linea desde la bomba hasta la casilla pasando por el centro de cada desvio atravesado.
+fn dibujar_explosiones(eventos: &[Evento]) -> String {
+    let mut svg = String::new();
+    let sombreadas = dibujo::casillas_alcanzadas(eventos);
+    let mut sombreadas: Vec<&Coordenada> = sombreadas.iter().collect();
+    sombreadas.sort_by_key(|coordenada| (coordenada.y, coordenada.x));
+    for coordenada in sombreadas {
+        svg.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{t}\" height=\"{t}\" fill=\"{}\" fill-opacity=\"0.3\"/>\n",
+            coordenada.x * TAMANIO_CELDA,
+            coordenada.y * TAMANIO_CELDA,
+            COLOR_EXPLOSION,
+            t = TAMANIO_CELDA
+        ));
+    }
+
+    for evento in eventos {
+        if let Evento::TileAlcanzado {
+            bomba,
+            tile,
+            desvios,
+        } = evento
+        {
+            let puntos: Vec<String> = std::iter::once(bomba)
+                .chain(desvios.iter())
+                .chain(std::iter::once(tile))
+                .map(|coordenada| {
+                    let (x, y) = centro(coordenada);
+                    format!("{},{}", x, y)
+                })
+                .collect();
+            svg.push_str(&format!(
+                "  <polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"4\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n",
+                puntos.join(" "),
+                COLOR_EXPLOSION
+            ));
+        }
+    }
+    svg
+}
+
+/// Exporta el mapa como una imagen SVG: la grilla, un dibujo por tile y, encima, el recorrido de las explosiones de los eventos.
+/// Los eventos se dibujan sobre el mapa tal como se recibe, por lo que conviene pasar el mapa de antes de la detonacion.
+/// Si no hay eventos, se dibuja solo el mapa.
+pub fn mapa_a_svg(mapa: &Mapa, eventos: &[Evento]) -> String {
+    let ancho = mapa.ancho * TAMANIO_CELDA;
+    let alto = mapa.alto * TAMANIO_CELDA;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{ancho}\" height=\"{alto}\" viewBox=\"0 0 {ancho} {alto}\">\n  <rect width=\"{ancho}\" height=\"{alto}\" fill=\"{}\"/>\n",
+        COLOR_FONDO
+    );
+    for y in 0..mapa.alto {
+        for x in 0..mapa.ancho {
+            svg.push_str(&format!(
+                "  <rect x=\"{}\" y=\"{}\" width=\"{t}\" height=\"{t}\" fill=\"none\" stroke=\"{}\"/>\n",
+                x * TAMANIO_CELDA,
+                y * TAMANIO_CELDA,
+                COLOR_GRILLA,
+                t = TAMANIO_CELDA
+            ));
+        }
+    }
+    svg.push_str(&dibujar_explosiones(eventos));
+    for (y, fila) in mapa.tiles.iter().enumerate() {
+        for (x, tile) in fila.iter().enumerate() {
+            svg.push_str(&dibujar_tile(tile, &Coordenada { x, y }));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::turno;
+
+    #[test]
+    fn test_mapa_a_svg_sin_explosiones() {
+        let mapa: Mapa = "F2 B1 S3\nR W DU\nJ _ _".parse().unwrap();
+        let svg = mapa_a_svg(&mapa, &[]);
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"120\" height=\"120\" viewBox=\"0 0 120 120\">\n"
+        ));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<circle").count(), 4);
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        assert!(svg.contains(">B1</text>"));
+        assert!(svg.contains(">S3</text>"));
+        assert!(svg.contains(">2</text>"));
+        assert!(svg.contains(">J</text>"));
+        // Desvio hacia arriba en la casilla (2, 1), con centro en (100, 60).
+        assert!(svg.contains("points=\"100,48 110,68 90,68\""));
+        assert!(!svg.contains("<polyline"));
+    }
+
+    #[test]
+    fn test_explosion_con_desvio() {
+        let mapa: Mapa = "B3 _ DD\n_ _ _\n_ _ F1".parse().unwrap();
+        let mut detonado = mapa.clone();
+        let eventos = turno::jugar_turno_con_eventos(&mut detonado, 0, 0).unwrap();
+        let svg = mapa_a_svg(&mapa, &eventos);
+        // Desde la bomba (20, 20) dobla en el desvio (100, 20); el enemigo en (100, 100) queda a 4 pasos y no se alcanza.
+        assert!(svg.contains("<polyline points=\"20,20 100,20 100,60\""));
+        assert!(svg.contains("<polyline points=\"20,20 60,20\""));
+        assert!(svg.contains("<polyline points=\"20,20 20,60\""));
+        assert!(!svg.contains("100,100\""));
+    }
+}
@@ -1,8 +1,18 @@
-use crate::modelo::{bomba::Bomba, coordenada::Coordenada, mapa::Mapa, tile::Tile};
+use crate::modelo::{
+    alcance::Alcance, bomba::Bomba, coordenada::Coordenada, evento::Evento, mapa::Mapa, tile::Tile,
+};
 
 /// Busca tiles en todas las direcciones y las devuelve en un vector.
 /// Busca en el siguiente orden de direcciones: arriba, abajo, derecha, izquierda.
 pub fn buscar_tiles(mapa: &Mapa, x_pos: usize, y_pos: usize, bomba: Bomba) -> Vec<Coordenada> {
+    buscar_alcances(mapa, x_pos, y_pos, bomba)
+        .into_iter()
+        .map(|alcanzado| alcanzado.coordenada)
+        .collect()
+}
+
+/// Igual que buscar_tiles, pero por cada tile devuelve tambien los desvios que atraveso la explosion para llegar.
+pub fn buscar_alcances(mapa: &Mapa, x_pos: usize, y_pos: usize, bomba: Bomba) -> Vec<Alcance> {
     let mut tiles_encontradas = Vec::new();
     let alcance = bomba.radio as usize;
     let especial = bomba.especial;
@@ -10,7 +20,7 @@ pub fn buscar_tiles(mapa: &Mapa, x_pos: usize, y_pos: usize, bomba: Bomba) -> Ve
     let direcciones = [(0, 1), (0, -1), (1, 0), (-1, 0)];
 
     for direccion in direcciones {
-        tiles_encontradas.append(&mut mapa.buscar_en_direccion(
+        tiles_encontradas.append(&mut mapa.recorrer_en_direccion(
             x_pos,
             y_pos,
             alcance,
@@ -22,30 +32,262 @@ pub fn buscar_tiles(mapa: &Mapa, x_pos: usize, y_pos: usize, bomba: Bomba) -> Ve
     tiles_encontradas
 }
 
+/// Busca las piedras que alcanza la explosion de la bomba: para una bomba normal, la piedra en la que se detiene cada direccion;
+/// para una especial, todas las piedras que atraviesa. Busca en el mismo orden de direcciones que buscar_tiles.
+pub fn buscar_piedras(mapa: &Mapa, x_pos: usize, y_pos: usize, bomba: Bomba) -> Vec<Alcance> {
+    if bomba.especial {
+        return buscar_alcances(mapa, x_pos, y_pos, bomba)
+            .into_iter()
+            .filter(|alcanzado| {
+                matches!(
+                    mapa.obtener_tile(alcanzado.coordenada.x, alcanzado.coordenada.y),
+                    Some(Tile::Piedra(_))
+                )
+            })
+            .collect();
+    }
+    let alcance = bomba.radio as usize;
+    [(0, 1), (0, -1), (1, 0), (-1, 0)]
+        .into_iter()
+        .filter_map(|(dx, dy)| mapa.piedra_en_direccion(x_pos, y_pos, alcance, false, dx, dy))
+        .collect()
+}
+
 /// Juega un turno en la posicion (x_pos, y_pos) del mapa.
 /// Si hay una bomba en esa posicion, destruye la bomba y busca tiles adyacentes, detonando otras bombas que se puedan encontrar en su alcance.
 /// Si dentro del alcance de la bomba hay un enemigo, le descuenta vida.
 pub fn jugar_turno(mapa: &mut Mapa, x_pos: usize, y_pos: usize) -> Result<(), &str> {
+    jugar_turno_con_eventos(mapa, x_pos, y_pos).map(|_| ())
+}
+
+/// Orden en el que se resuelven las reacciones en cadena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModoResolucion {
+    /// Cada bomba alcanzada explota en el momento en el que la explosion que la alcanza la encuentra,
+    /// antes de seguir con el resto de las casillas de esa explosion.
+    Profundidad,
+    /// Las bombas explotan por ondas: todas las bombas alcanzadas por una onda explotan juntas en la siguiente,
+    /// calculando su alcance sobre el mismo estado del mapa, y sus efectos se aplican despues.
+    Ondas,
+}
+
+/// Juega un turno igual que jugar_turno y devuelve, en orden, los eventos ocurridos durante la explosion.
+/// Las reacciones en cadena se resuelven en ModoResolucion::Profundidad.
+/// Si no hay bomba en la posicion, devuelve un error y no modifica el mapa.
+pub fn jugar_turno_con_eventos(
+    mapa: &mut Mapa,
+    x_pos: usize,
+    y_pos: usize,
+) -> Result<Vec<Evento>, &'static str> {
+    jugar_turno_con_modo(mapa, x_pos, y_pos, ModoResolucion::Profundidad)
+}
+
+/// Juega un turno resolviendo las reacciones en cadena segun el modo indicado y devuelve, en orden, los eventos ocurridos.
+/// Si no hay bomba en la posicion, devuelve un error y no modifica el mapa.
+pub fn jugar_turno_con_modo(
+    mapa: &mut Mapa,
+    x_pos: usize,
+    y_pos: usize,
+    modo: ModoResolucion,
+) -> Result<Vec<Evento>, &'static str> {
+    let mut eventos = Vec::new();
+    match modo {
+        ModoResolucion::Profundidad => detonar(mapa, x_pos, y_pos, None, &mut eventos)?,
+        ModoResolucion::Ondas => detonar_por_ondas(mapa, x_pos, y_pos, &mut eventos)?,
+    }
+    Ok(eventos)
+}
+
+/// Detona unicamente la bomba en la posicion (x_pos, y_pos) y devuelve, en orden, los eventos ocurridos.
+/// Las bombas alcanzadas no detonan: quedan en el mapa y solo se registran como TileAlcanzado.
+/// Si no hay bomba en la posicion, devuelve un error y no modifica el mapa.
+pub fn detonar_sin_cadena(
+    mapa: &mut Mapa,
+    x_pos: usize,
+    y_pos: usize,
+) -> Result<Vec<Evento>, &'static str> {
+    let mut eventos = Vec::new();
+    let explosion = explotar(mapa, x_pos, y_pos, None, &mut eventos)?;
+    for alcanzado in explosion.pendientes {
+        let tile = alcanzado.coordenada;
+        eventos.push(Evento::TileAlcanzado {
+            bomba: explosion.origen,
+            tile,
+            desvios: alcanzado.desvios,
+        });
+        if let Some(Tile::Enemigo(_)) = mapa.obtener_tile(tile.x, tile.y) {
+            atacar(mapa, explosion.origen, tile, explosion.dmg, &mut eventos);
+        }
+    }
+    Ok(eventos)
+}
+
+/// Ataca al enemigo que se encuentre en la posicion tile con la bomba origen, registrando el resultado.
+fn atacar(
+    mapa: &mut Mapa,
+    origen: Coordenada,
+    tile: Coordenada,
+    dmg: u32,
+    eventos: &mut Vec<Evento>,
+) {
+    match mapa.atacar_enemigo(origen.x, origen.y, tile.x, tile.y, dmg) {
+        Some(0) => eventos.push(Evento::EnemigoEliminado {
+            bomba: origen,
+            enemigo: tile,
+        }),
+        Some(vida_restante) => eventos.push(Evento::EnemigoDaniado {
+            bomba: origen,
+            enemigo: tile,
+            vida_restante,
+        }),
+        None => {}
+    }
+}
+
+/// Explosion en curso durante una detonacion en profundidad.
+/// Guarda las casillas alcanzadas que todavia no se procesaron.
+struct Explosion {
+    origen: Coordenada,
+    dmg: u32,
+    pendientes: std::vec::IntoIter<Alcance>,
+}
+
+/// Hace explotar la bomba en la posicion (x_pos, y_pos): calcula su alcance, la destruye y registra el evento.
+/// Devuelve la explosion con las casillas alcanzadas pendientes de procesar.
+fn explotar(
+    mapa: &mut Mapa,
+    x_pos: usize,
+    y_pos: usize,
+    disparada_por: Option<Coordenada>,
+    eventos: &mut Vec<Evento>,
+) -> Result<Explosion, &'static str> {
     match mapa.obtener_tile(x_pos, y_pos) {
         Some(Tile::BombaNormal(bomba)) | Some(Tile::BombaEspecial(bomba)) => {
-            let tiles_adyacentes: Vec<Coordenada> = buscar_tiles(mapa, x_pos, y_pos, bomba.clone());
+            let origen = bomba.coordenadas();
+            let dmg = bomba.dmg;
+            let tiles_adyacentes = buscar_alcances(mapa, x_pos, y_pos, bomba.clone());
             mapa.destruir_tile(x_pos, y_pos);
-            for tile in tiles_adyacentes {
+            eventos.push(Evento::BombaDetonada {
+                bomba: origen,
+                disparada_por,
+            });
+            Ok(Explosion {
+                origen,
+                dmg,
+                pendientes: tiles_adyacentes.into_iter(),
+            })
+        }
+        _ => Err("No hay bomba en esa posicion"),
+    }
+}
+
+/// Detona la bomba en la posicion (x_pos, y_pos) registrando los eventos ocurridos.
+/// Las bombas alcanzadas por la explosion se detonan en el momento en el que se las encuentra,
+/// y recien cuando terminan se sigue con el resto de las casillas de la explosion que las alcanzo.
+/// Usa una pila de explosiones en curso en lugar de recursion, para no desbordar el stack en mapas grandes.
+fn detonar(
+    mapa: &mut Mapa,
+    x_pos: usize,
+    y_pos: usize,
+    disparada_por: Option<Coordenada>,
+    eventos: &mut Vec<Evento>,
+) -> Result<(), &'static str> {
+    let mut en_curso = vec![explotar(mapa, x_pos, y_pos, disparada_por, eventos)?];
+    while let Some(explosion) = en_curso.last_mut() {
+        let (origen, dmg) = (explosion.origen, explosion.dmg);
+        let alcanzado = match explosion.pendientes.next() {
+            Some(alcanzado) => alcanzado,
+            None => {
+                en_curso.pop();
+                continue;
+            }
+        };
+        let tile = alcanzado.coordenada;
+        eventos.push(Evento::TileAlcanzado {
+            bomba: origen,
+            tile,
+            desvios: alcanzado.desvios,
+        });
+        match mapa.obtener_tile(tile.x, tile.y) {
+            Some(Tile::Enemigo(_)) => atacar(mapa, origen, tile, dmg, eventos),
+            Some(Tile::BombaNormal(_)) | Some(Tile::BombaEspecial(_)) => {
+                eventos.push(Evento::ReaccionEnCadena {
+                    origen,
+                    bomba: tile,
+                });
+                if let Ok(encadenada) = explotar(mapa, tile.x, tile.y, Some(origen), eventos) {
+                    en_curso.push(encadenada);
+                }
+            }
+            _ => continue,
+        }
+    }
+    Ok(())
+}
+
+/// Detona la bomba en la posicion (x_pos, y_pos) resolviendo las reacciones en cadena por ondas.
+/// En cada onda primero se calcula el alcance de todas sus bombas, despues se las destruye y por ultimo se aplican los efectos.
+/// Una bomba alcanzada por varias bombas de la misma onda se detona una sola vez, disparada por la primera que la alcanzo.
+fn detonar_por_ondas(
+    mapa: &mut Mapa,
+    x_pos: usize,
+    y_pos: usize,
+    eventos: &mut Vec<Evento>,
+) -> Result<(), &'static str> {
+    let mut onda: Vec<(Bomba, Option<Coordenada>)> = match mapa.obtener_tile(x_pos, y_pos) {
+        Some(Tile::BombaNormal(bomba)) | Some(Tile::BombaEspecial(bomba)) => {
+            vec![(bomba.clone(), None)]
+        }
+        _ => return Err("No hay bomba en esa posicion"),
+    };
+
+    while !onda.is_empty() {
+        let explosiones: Vec<(Bomba, Vec<Alcance>)> = onda
+            .iter()
+            .map(|(bomba, _)| {
+                let alcances = buscar_alcances(mapa, bomba.x, bomba.y, bomba.clone());
+                (bomba.clone(), alcances)
+            })
+            .collect();
+        for (bomba, disparada_por) in onda.iter() {
+            mapa.destruir_tile(bomba.x, bomba.y);
+            eventos.push(Evento::BombaDetonada {
+                bomba: bomba.coordenadas(),
+                disparada_por: *disparada_por,
+            });
+        }
+
+        let mut siguiente_onda: Vec<(Bomba, Option<Coordenada>)> = Vec::new();
+        for (bomba, alcances) in explosiones {
+            let origen = bomba.coordenadas();
+            for alcanzado in alcances {
+                let tile = alcanzado.coordenada;
+                eventos.push(Evento::TileAlcanzado {
+                    bomba: origen,
+                    tile,
+                    desvios: alcanzado.desvios,
+                });
                 match mapa.obtener_tile(tile.x, tile.y) {
-                    Some(Tile::Enemigo(_)) => {
-                        mapa.atacar_enemigo(x_pos, y_pos, tile.x, tile.y, 1);
-                    }
-                    Some(Tile::BombaNormal(bomba_encontrada))
-                    | Some(Tile::BombaEspecial(bomba_encontrada)) => {
-                        let _ = jugar_turno(mapa, bomba_encontrada.x, bomba_encontrada.y);
+                    Some(Tile::Enemigo(_)) => atacar(mapa, origen, tile, bomba.dmg, eventos),
+                    Some(Tile::BombaNormal(encontrada)) | Some(Tile::BombaEspecial(encontrada)) => {
+                        if siguiente_onda
+                            .iter()
+                            .all(|(pendiente, _)| pendiente.coordenadas() != tile)
+                        {
+                            eventos.push(Evento::ReaccionEnCadena {
+                                origen,
+                                bomba: tile,
+                            });
+                            siguiente_onda.push((encontrada.clone(), Some(origen)));
+                        }
                     }
                     _ => continue,
                 }
             }
-            Ok(())
         }
-        _ => Err("No hay bomba en esa posicion"),
+        onda = siguiente_onda;
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -59,7 +301,8 @@ mod test {
     #[test]
     fn test_fuera_de_rango() {
         let mut mapa = Mapa {
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
             tiles: vec![
                 vec![Tile::Vacio, Tile::Vacio, Tile::Vacio],
                 vec![
@@ -77,7 +320,8 @@ mod test {
     #[test]
     fn test_detonar() {
         let mut mapa = Mapa {
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
             tiles: vec![
                 vec![
                     Tile::Vacio,
@@ -101,7 +345,8 @@ mod test {
     #[test]
     fn test_detonar_radio_mayor_a_len() {
         let mut mapa = Mapa {
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
             tiles: vec![
                 vec![
                     Tile::Vacio,
@@ -125,7 +370,8 @@ mod test {
     #[test]
     fn test_detonar_radio_0() {
         let mut mapa = Mapa {
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
             tiles: vec![
                 vec![
                     Tile::Vacio,
@@ -149,7 +395,8 @@ mod test {
     #[test]
     fn test_buscar_tiles() {
         let mapa = Mapa {
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
             tiles: vec![
                 vec![Tile::Vacio, Tile::Vacio, Tile::Vacio],
                 vec![
@@ -175,7 +422,8 @@ mod test {
     #[test]
     fn test_buscar_mapa_vacio() {
         let mapa = Mapa {
-            side_size: 0,
+            ancho: 0,
+            alto: 0,
             tiles: vec![],
         };
         let tiles_encontradas = buscar_tiles(&mapa, 1, 1, Bomba::crear(1, 1, 2, false));
@@ -185,54 +433,23 @@ mod test {
     #[test]
     fn test_buscar_mapa_lleno_piedras() {
         let mapa = Mapa {
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
             tiles: vec![
                 vec![
-                    Tile::Piedra(Obstaculo {
-                        x: 0,
-                        y: 0,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 1,
-                        y: 0,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 2,
-                        y: 0,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(0, 0, true)),
+                    Tile::Piedra(Obstaculo::crear(1, 0, true)),
+                    Tile::Piedra(Obstaculo::crear(2, 0, true)),
                 ],
                 vec![
-                    Tile::Piedra(Obstaculo {
-                        x: 0,
-                        y: 1,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(0, 1, true)),
                     Tile::BombaNormal(Bomba::crear(1, 1, 2, false)),
-                    Tile::Piedra(Obstaculo {
-                        x: 2,
-                        y: 1,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(2, 1, true)),
                 ],
                 vec![
-                    Tile::Piedra(Obstaculo {
-                        x: 0,
-                        y: 2,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 1,
-                        y: 2,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 2,
-                        y: 2,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(0, 2, true)),
+                    Tile::Piedra(Obstaculo::crear(1, 2, true)),
+                    Tile::Piedra(Obstaculo::crear(2, 2, true)),
                 ],
             ],
         };
@@ -243,54 +460,23 @@ mod test {
     #[test]
     fn test_buscar_mapa_lleno_piedras_bomba_especial() {
         let mapa = Mapa {
-            side_size: 3,
+            ancho: 3,
+            alto: 3,
             tiles: vec![
                 vec![
-                    Tile::Piedra(Obstaculo {
-                        x: 0,
-                        y: 0,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 1,
-                        y: 0,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 2,
-                        y: 0,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(0, 0, true)),
+                    Tile::Piedra(Obstaculo::crear(1, 0, true)),
+                    Tile::Piedra(Obstaculo::crear(2, 0, true)),
                 ],
                 vec![
-                    Tile::Piedra(Obstaculo {
-                        x: 0,
-                        y: 1,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(0, 1, true)),
                     Tile::BombaEspecial(Bomba::crear(1, 1, 2, true)),
-                    Tile::Piedra(Obstaculo {
-                        x: 2,
-                        y: 1,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(2, 1, true)),
                 ],
                 vec![
-                    Tile::Piedra(Obstaculo {
-                        x: 0,
-                        y: 2,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 1,
-                        y: 2,
-                        pasable: true,
-                    }),
-                    Tile::Piedra(Obstaculo {
-                        x: 2,
-                        y: 2,
-                        pasable: true,
-                    }),
+                    Tile::Piedra(Obstaculo::crear(0, 2, true)),
+                    Tile::Piedra(Obstaculo::crear(1, 2, true)),
+                    Tile::Piedra(Obstaculo::crear(2, 2, true)),
                 ],
             ],
         };
@@ -309,7 +495,8 @@ mod test {
     #[test]
     fn test_bomba_pasa_dos_veces_por_enemigo() {
         let mut mapa = Mapa {
-            side_size: 5,
+            ancho: 5,
+            alto: 5,
             tiles: vec![
                 vec![
                     Tile::Vacio,
@@ -355,11 +542,278 @@ mod test {
 
         let _ = jugar_turno(&mut mapa, 1, 2);
         let enemigo = mapa.obtener_tile(1, 1).unwrap();
-        let _ = match enemigo {
+        match enemigo {
             Tile::Enemigo(enemigo) => {
                 assert_eq!(enemigo.vida, 1);
             }
             _ => assert_eq!(*enemigo, Tile::Enemigo(Enemigo::crear(1, 1, 2))),
         };
     }
+
+    #[test]
+    fn test_eventos_reaccion_en_cadena() {
+        let mut mapa = Mapa {
+            ancho: 3,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 1, false)),
+                Tile::BombaNormal(Bomba::crear(1, 0, 1, false)),
+                Tile::Enemigo(Enemigo::crear(2, 0, 2)),
+            ]],
+        };
+        let eventos = jugar_turno_con_eventos(&mut mapa, 0, 0).unwrap();
+        let primera = Coordenada { x: 0, y: 0 };
+        let segunda = Coordenada { x: 1, y: 0 };
+        let enemigo = Coordenada { x: 2, y: 0 };
+        assert_eq!(
+            eventos,
+            vec![
+                Evento::BombaDetonada {
+                    bomba: primera,
+                    disparada_por: None
+                },
+                Evento::TileAlcanzado {
+                    bomba: primera,
+                    tile: segunda,
+                    desvios: vec![]
+                },
+                Evento::ReaccionEnCadena {
+                    origen: primera,
+                    bomba: segunda
+                },
+                Evento::BombaDetonada {
+                    bomba: segunda,
+                    disparada_por: Some(primera)
+                },
+                Evento::TileAlcanzado {
+                    bomba: segunda,
+                    tile: enemigo,
+                    desvios: vec![]
+                },
+                Evento::EnemigoDaniado {
+                    bomba: segunda,
+                    enemigo,
+                    vida_restante: 1
+                },
+                Evento::TileAlcanzado {
+                    bomba: segunda,
+                    tile: primera,
+                    desvios: vec![]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_eventos_desvio_y_enemigo_eliminado() {
+        let mut mapa = Mapa {
+            ancho: 3,
+            alto: 3,
+            tiles: vec![
+                vec![
+                    Tile::Vacio,
+                    Tile::Vacio,
+                    Tile::Enemigo(Enemigo::crear(2, 0, 1)),
+                ],
+                vec![Tile::Vacio, Tile::Vacio, Tile::Vacio],
+                vec![
+                    Tile::BombaNormal(Bomba::crear(0, 2, 4, false)),
+                    Tile::Vacio,
+                    Tile::Desvio(Desvio {
+                        x: 2,
+                        y: 2,
+                        direccion: Direccion::Arriba,
+                    }),
+                ],
+            ],
+        };
+        let eventos = jugar_turno_con_eventos(&mut mapa, 0, 2).unwrap();
+        let bomba = Coordenada { x: 0, y: 2 };
+        let enemigo = Coordenada { x: 2, y: 0 };
+        assert!(eventos.contains(&Evento::TileAlcanzado {
+            bomba,
+            tile: enemigo,
+            desvios: vec![Coordenada { x: 2, y: 2 }]
+        }));
+        assert!(eventos.contains(&Evento::EnemigoEliminado { bomba, enemigo }));
+        assert_eq!(mapa.obtener_tile(2, 0), Some(&Tile::Vacio));
+    }
+
+    #[test]
+    fn test_eventos_sin_bomba() {
+        let mut mapa = Mapa {
+            ancho: 1,
+            alto: 1,
+            tiles: vec![vec![Tile::Vacio]],
+        };
+        let eventos = jugar_turno_con_eventos(&mut mapa, 0, 0);
+        assert_eq!(eventos, Err("No hay bomba en esa posicion"));
+    }
+
+    #[test]
+    fn test_bomba_pesada() {
+        let mut mapa = Mapa {
+            ancho: 3,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::Enemigo(Enemigo::crear(0, 0, 3)),
+                Tile::BombaNormal(Bomba::crear_con_dmg(1, 0, 1, false, 3)),
+                Tile::Enemigo(Enemigo::crear(2, 0, 5)),
+            ]],
+        };
+        let _ = jugar_turno(&mut mapa, 1, 0);
+        assert_eq!(mapa.obtener_tile(0, 0), Some(&Tile::Vacio));
+        match mapa.obtener_tile(2, 0) {
+            Some(Tile::Enemigo(enemigo)) => assert_eq!(enemigo.vida, 2),
+            otro => panic!("Se esperaba un enemigo, se encontro {:?}", otro),
+        }
+    }
+
+    fn mapa_cadena_en_fila() -> Mapa {
+        Mapa {
+            ancho: 4,
+            alto: 1,
+            tiles: vec![vec![
+                Tile::BombaNormal(Bomba::crear(0, 0, 1, false)),
+                Tile::BombaNormal(Bomba::crear(1, 0, 1, false)),
+                Tile::BombaNormal(Bomba::crear(2, 0, 2, false)),
+                Tile::Enemigo(Enemigo::crear(3, 0, 3)),
+            ]],
+        }
+    }
+
+    /// Devuelve, en orden, cada bomba detonada junto con la bomba que la disparo.
+    fn detonaciones(eventos: &[Evento]) -> Vec<(Coordenada, Option<Coordenada>)> {
+        eventos
+            .iter()
+            .filter_map(|evento| match evento {
+                Evento::BombaDetonada {
+                    bomba,
+                    disparada_por,
+                } => Some((*bomba, *disparada_por)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_modo_profundidad() {
+        let mut mapa = mapa_cadena_en_fila();
+        let eventos = jugar_turno_con_modo(&mut mapa, 2, 0, ModoResolucion::Profundidad).unwrap();
+        let (a, b, c) = (
+            Coordenada { x: 0, y: 0 },
+            Coordenada { x: 1, y: 0 },
+            Coordenada { x: 2, y: 0 },
+        );
+        assert_eq!(
+            detonaciones(&eventos),
+            vec![(c, None), (b, Some(c)), (a, Some(b))]
+        );
+    }
+
+    #[test]
+    fn test_modo_ondas() {
+        let mut mapa = mapa_cadena_en_fila();
+        let eventos = jugar_turno_con_modo(&mut mapa, 2, 0, ModoResolucion::Ondas).unwrap();
+        let (a, b, c) = (
+            Coordenada { x: 0, y: 0 },
+            Coordenada { x: 1, y: 0 },
+            Coordenada { x: 2, y: 0 },
+        );
+        assert_eq!(
+            detonaciones(&eventos),
+            vec![(c, None), (b, Some(c)), (a, Some(c))]
+        );
+        // Las dos bombas de la segunda onda explotan antes de que se aplique cualquiera de sus efectos.
+        let posicion_segunda_detonacion = eventos
+            .iter()
+            .position(|evento| matches!(evento, Evento::BombaDetonada { bomba, .. } if *bomba == a))
+            .unwrap();
+        let primer_alcance_de_b = eventos
+            .iter()
+            .position(|evento| matches!(evento, Evento::TileAlcanzado { bomba, .. } if *bomba == b))
+            .unwrap();
+        assert!(posicion_segunda_detonacion < primer_alcance_de_b);
+
+        match mapa.obtener_tile(3, 0) {
+            Some(Tile::Enemigo(enemigo)) => assert_eq!(enemigo.vida, 2),
+            otro => panic!("Se esperaba un enemigo, se encontro {:?}", otro),
+        }
+    }
+
+    #[test]
+    fn test_modo_ondas_sin_bomba() {
+        let mut mapa = mapa_cadena_en_fila();
+        let eventos = jugar_turno_con_modo(&mut mapa, 3, 0, ModoResolucion::Ondas);
+        assert_eq!(eventos, Err("No hay bomba en esa posicion"));
+        assert_eq!(mapa, mapa_cadena_en_fila());
+    }
+
+    #[test]
+    fn test_detonar_sin_cadena() {
+        let mut mapa = mapa_cadena_en_fila();
+        let original = mapa_cadena_en_fila();
+        let eventos = detonar_sin_cadena(&mut mapa, 0, 0).unwrap();
+        assert_eq!(detonaciones(&eventos).len(), 1);
+        assert!(!eventos
+            .iter()
+            .any(|evento| matches!(evento, Evento::ReaccionEnCadena { .. })));
+        assert_eq!(mapa.obtener_tile(0, 0), Some(&Tile::Vacio));
+        assert_eq!(mapa.obtener_tile(1, 0), original.obtener_tile(1, 0));
+
+        assert_eq!(
+            detonar_sin_cadena(&mut mapa, 0, 0),
+            Err("No hay bomba en esa posicion")
+        );
+    }
+
+    #[test]
+    fn test_mapa_grande_lleno_de_bombas() {
+        let lado = 500;
+        let tiles = (0..lado)
+            .map(|y| {
+                (0..lado)
+                    .map(|x| Tile::BombaNormal(Bomba::crear(x, y, 1, false)))
+                    .collect()
+            })
+            .collect();
+        let mut mapa = Mapa {
+            ancho: lado,
+            alto: lado,
+            tiles,
+        };
+        let eventos = jugar_turno_con_eventos(&mut mapa, 0, 0).unwrap();
+        assert_eq!(detonaciones(&eventos).len(), lado * lado);
+        assert!(mapa.tiles.iter().flatten().all(|tile| *tile == Tile::Vacio));
+    }
+
+    #[test]
+    fn test_buscar_piedras() {
+        let mapa: Mapa = "R B2 _ R\n_ R _ _\n_ R _ _".parse().unwrap();
+        let piedras: Vec<Coordenada> = buscar_piedras(&mapa, 1, 0, Bomba::crear(1, 0, 2, false))
+            .into_iter()
+            .map(|alcanzado| alcanzado.coordenada)
+            .collect();
+        assert_eq!(
+            piedras,
+            vec![
+                Coordenada { x: 1, y: 1 },
+                Coordenada { x: 3, y: 0 },
+                Coordenada { x: 0, y: 0 }
+            ]
+        );
+        let piedras: Vec<Coordenada> = buscar_piedras(&mapa, 1, 0, Bomba::crear(1, 0, 2, true))
+            .into_iter()
+            .map(|alcanzado| alcanzado.coordenada)
+            .collect();
+        assert_eq!(
+            piedras,
+            vec![
+                Coordenada { x: 1, y: 1 },
+                Coordenada { x: 1, y: 2 },
+                Coordenada { x: 3, y: 0 },
+                Coordenada { x: 0, y: 0 }
+            ]
+        );
+    }
 }
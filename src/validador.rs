@@ -0,0 +1,370 @@
+use crate::cobertura;
+use crate::modelo::{
+    coordenada::Coordenada, desvio::Desvio, mapa::Mapa, movimiento::Movimiento, tile::Tile,
+};
+use crate::turno;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Gravedad de un hallazgo del validador.
+/// Un error impide resolver el mapa; una advertencia indica algo que probablemente no sea intencional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severidad {
+    Advertencia,
+    Error,
+}
+
+/// Problema semantico encontrado en un mapa que se pudo transformar correctamente.
+/// coordenada es None si el problema es del mapa entero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hallazgo {
+    pub coordenada: Option<Coordenada>,
+    pub severidad: Severidad,
+    pub mensaje: String,
+}
+
+impl fmt::Display for Severidad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severidad::Advertencia => write!(f, "advertencia"),
+            Severidad::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Muestra el hallazgo en una linea. Ejemplo: error (3, 1): el enemigo no es alcanzado por ninguna bomba.
+impl fmt::Display for Hallazgo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.coordenada {
+            Some(coordenada) => write!(
+                f,
+                "{} ({}, {}): {}",
+                self.severidad, coordenada.x, coordenada.y, self.mensaje
+            ),
+            None => write!(f, "{}: {}", self.severidad, self.mensaje),
+        }
+    }
+}
+
+impl Hallazgo {
+    fn crear(coordenada: Option<Coordenada>, severidad: Severidad, mensaje: &str) -> Hallazgo {
+        Hallazgo {
+            coordenada,
+            severidad,
+            mensaje: mensaje.to_string(),
+        }
+    }
+}
+
+/// Devuelve la casilla a la que apunta el desvio, o None si apunta fuera del mapa.
+fn siguiente_casilla(mapa: &Mapa, desvio: &Desvio) -> Option<Coordenada> {
+    let (dx, dy) = desvio.direccion.desplazamiento();
+    let x = desvio.x.checked_add_signed(dx as isize)?;
+    let y = desvio.y.checked_add_signed(dy as isize)?;
+    mapa.obtener_tile(x, y)?;
+    Some(Coordenada { x, y })
+}
+
+/// Sigue el recorrido de una explosion que sale del desvio, sin limite de alcance.
+/// Solo las paredes y el borde la detienen, como a una bomba especial.
+/// Devuelve los desvios del ciclo si el recorrido vuelve a un desvio por el que ya paso.
+fn buscar_ciclo(mapa: &Mapa, inicio: &Desvio) -> Option<Vec<Coordenada>> {
+    let mut recorridos: Vec<Coordenada> = vec![Coordenada {
+        x: inicio.x,
+        y: inicio.y,
+    }];
+    let mut desvio = *inicio;
+    let mut casilla = siguiente_casilla(mapa, &desvio);
+    while let Some(actual) = casilla {
+        match mapa.obtener_tile(actual.x, actual.y) {
+            Some(Tile::Pared(_)) | None => return None,
+            Some(Tile::Desvio(siguiente)) => {
+                if let Some(indice) = recorridos.iter().position(|c| *c == actual) {
+                    return Some(recorridos.split_off(indice));
+                }
+                recorridos.push(actual);
+                desvio = *siguiente;
+                casilla = siguiente_casilla(mapa, &desvio);
+            }
+            Some(_) => {
+                let (dx, dy) = desvio.direccion.desplazamiento();
+                casilla = actual
+                    .x
+                    .checked_add_signed(dx as isize)
+                    .zip(actual.y.checked_add_signed(dy as isize))
+                    .filter(|(x, y)| mapa.obtener_tile(*x, *y).is_some())
+                    .map(|(x, y)| Coordenada { x, y });
+            }
+        }
+    }
+    None
+}
+
+/// Revisa los desvios: que no apunten fuera del mapa o a una pared, y que no formen ciclos.
+/// Cada ciclo se informa una sola vez, en el desvio que aparece primero recorriendo el mapa por filas.
+fn validar_desvios(mapa: &Mapa, hallazgos: &mut Vec<Hallazgo>) {
+    let mut en_ciclos: HashSet<Coordenada> = HashSet::new();
+    for tile in mapa.tiles.iter().flatten() {
+        let desvio = match tile {
+            Tile::Desvio(desvio) => desvio,
+            _ => continue,
+        };
+        let coordenada = Some(Coordenada {
+            x: desvio.x,
+            y: desvio.y,
+        });
+        match siguiente_casilla(mapa, desvio) {
+            None => hallazgos.push(Hallazgo::crear(
+                coordenada,
+                Severidad::Advertencia,
+                "el desvio apunta fuera del mapa",
+            )),
+            Some(siguiente) => {
+                if let Some(Tile::Pared(_)) = mapa.obtener_tile(siguiente.x, siguiente.y) {
+                    hallazgos.push(Hallazgo::crear(
+                        coordenada,
+                        Severidad::Advertencia,
+                        "el desvio apunta directamente a una pared",
+                    ));
+                }
+            }
+        }
+        if let Some(ciclo) = buscar_ciclo(mapa, desvio) {
+            if ciclo.iter().any(|c| en_ciclos.contains(c)) {
+                continue;
+            }
+            let recorrido: Vec<String> = ciclo
+                .iter()
+                .map(|c| format!("({}, {})", c.x, c.y))
+                .collect();
+            hallazgos.push(Hallazgo {
+                coordenada: ciclo.iter().min_by_key(|c| (c.y, c.x)).copied(),
+                severidad: Severidad::Advertencia,
+                mensaje: format!(
+                    "los desvios forman un ciclo: {} -> ({}, {})",
+                    recorrido.join(" -> "),
+                    ciclo[0].x,
+                    ciclo[0].y
+                ),
+            });
+            en_ciclos.extend(ciclo);
+        }
+    }
+}
+
+/// Revisa las bombas: que haya al menos una y que cada una alcance algun enemigo u otra bomba.
+fn validar_bombas(mapa: &Mapa, hallazgos: &mut Vec<Hallazgo>) {
+    let mut hay_bombas = false;
+    for tile in mapa.tiles.iter().flatten() {
+        let bomba = match tile {
+            Tile::BombaNormal(bomba) | Tile::BombaEspecial(bomba) => bomba,
+            _ => continue,
+        };
+        hay_bombas = true;
+        let alcanza_algo = turno::buscar_tiles(mapa, bomba.x, bomba.y, bomba.clone())
+            .into_iter()
+            .filter(|coordenada| *coordenada != bomba.coordenadas())
+            .any(|coordenada| {
+                matches!(
+                    mapa.obtener_tile(coordenada.x, coordenada.y),
+                    Some(Tile::Enemigo(_) | Tile::BombaNormal(_) | Tile::BombaEspecial(_))
+                )
+            });
+        if !alcanza_algo {
+            hallazgos.push(Hallazgo::crear(
+                Some(bomba.coordenadas()),
+                Severidad::Advertencia,
+                "la explosion de la bomba no alcanza ningun enemigo ni otra bomba",
+            ));
+        }
+    }
+    if !hay_bombas {
+        hallazgos.push(Hallazgo::crear(
+            None,
+            Severidad::Error,
+            "el mapa no tiene bombas",
+        ));
+    }
+}
+
+/// Devuelve una copia del mapa sin piedras, como quedaria si las explosiones las destruyeran todas.
+fn sin_piedras(mapa: &Mapa) -> Mapa {
+    let mut mapa = mapa.clone();
+    for tile in mapa.tiles.iter_mut().flatten() {
+        if matches!(tile, Tile::Piedra(_)) {
+            *tile = Tile::Vacio;
+        }
+    }
+    mapa
+}
+
+/// Revisa que cada enemigo sea alcanzado por alguna bomba, contando desvios y reacciones en cadena.
+/// Un enemigo quieto que no se alcanza desde el mapa original es un error, salvo que se alcance sacando las piedras:
+/// en una Partida con piedras_destructibles las explosiones pueden abrirle camino, asi que es solo una advertencia.
+/// Los enemigos que se mueven no se revisan: pueden entrar al alcance de una bomba durante la partida.
+fn validar_enemigos(mapa: &Mapa, hallazgos: &mut Vec<Hallazgo>) {
+    let cobertura = cobertura::calcular_cobertura(mapa);
+    let mut cobertura_sin_piedras = None;
+    for tile in mapa.tiles.iter().flatten() {
+        if let Tile::Enemigo(enemigo) = tile {
+            if enemigo.movimiento != Movimiento::Quieto || cobertura[enemigo.y][enemigo.x] > 0 {
+                continue;
+            }
+            let sin_piedras = cobertura_sin_piedras
+                .get_or_insert_with(|| cobertura::calcular_cobertura(&sin_piedras(mapa)));
+            let hallazgo = if sin_piedras[enemigo.y][enemigo.x] > 0 {
+                Hallazgo::crear(
+                    Some(enemigo.coordenadas()),
+                    Severidad::Advertencia,
+                    "el enemigo solo es alcanzado si las explosiones destruyen piedras",
+                )
+            } else {
+                Hallazgo::crear(
+                    Some(enemigo.coordenadas()),
+                    Severidad::Error,
+                    "el enemigo no es alcanzado por ninguna bomba",
+                )
+            };
+            hallazgos.push(hallazgo);
+        }
+    }
+}
+
+/// Revisa que haya a lo sumo un jugador. Se informa cada jugador que sobra, en el orden en el que aparecen recorriendo el mapa por filas.
+fn validar_jugadores(mapa: &Mapa, hallazgos: &mut Vec<Hallazgo>) {
+    let jugadores = mapa.tiles.iter().flatten().filter_map(|tile| match tile {
+        Tile::Jugador(jugador) => Some(jugador.coordenadas()),
+        _ => None,
+    });
+    for coordenada in jugadores.skip(1) {
+        hallazgos.push(Hallazgo::crear(
+            Some(coordenada),
+            Severidad::Error,
+            "el mapa tiene mas de un jugador",
+        ));
+    }
+}
+
+/// Busca problemas semanticos en el mapa y los devuelve ordenados por posicion, con los del mapa entero al final.
+pub fn validar(mapa: &Mapa) -> Vec<Hallazgo> {
+    let mut hallazgos = Vec::new();
+    validar_desvios(mapa, &mut hallazgos);
+    validar_bombas(mapa, &mut hallazgos);
+    validar_enemigos(mapa, &mut hallazgos);
+    validar_jugadores(mapa, &mut hallazgos);
+    hallazgos.sort_by_key(|hallazgo| match hallazgo.coordenada {
+        Some(coordenada) => (0, coordenada.y, coordenada.x),
+        None => (1, 0, 0),
+    });
+    hallazgos
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bomberman_game::transformar_a_mapa;
+
+    fn mensajes(mapa: &str) -> Vec<String> {
+        let mapa: Mapa = mapa.parse().unwrap();
+        validar(&mapa).iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn test_mapas_sin_hallazgos() {
+        for path in ["mapas/mapa_1.txt", "mapas/mapa_3.txt"] {
+            let mapa = transformar_a_mapa(path).unwrap();
+            assert_eq!(validar(&mapa), vec![], "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_mapa_2_tiene_un_enemigo_detras_de_una_piedra() {
+        let mapa = transformar_a_mapa("mapas/mapa_2.txt").unwrap();
+        let hallazgos = validar(&mapa);
+        assert!(hallazgos
+            .iter()
+            .all(|hallazgo| hallazgo.severidad == Severidad::Advertencia));
+        assert_eq!(
+            hallazgos
+                .into_iter()
+                .filter(|hallazgo| hallazgo.coordenada == Some(Coordenada { x: 4, y: 2 }))
+                .collect::<Vec<Hallazgo>>(),
+            vec![Hallazgo::crear(
+                Some(Coordenada { x: 4, y: 2 }),
+                Severidad::Advertencia,
+                "el enemigo solo es alcanzado si las explosiones destruyen piedras"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_desvio_hacia_pared_y_fuera_del_mapa() {
+        assert_eq!(
+            mensajes("B2 DR W\nF1 _ DD"),
+            vec![
+                "advertencia (1, 0): el desvio apunta directamente a una pared",
+                "advertencia (2, 1): el desvio apunta fuera del mapa",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ciclo_de_desvios() {
+        assert_eq!(
+            mensajes("B1 DR _ DD\nF1 _ _ _\n_ DU _ DL"),
+            vec!["advertencia (1, 0): los desvios forman un ciclo: (1, 0) -> (3, 0) -> (3, 2) -> (1, 2) -> (1, 0)"]
+        );
+    }
+
+    #[test]
+    fn test_ciclo_cortado_por_pared() {
+        assert_eq!(
+            mensajes("B1 DR W DD\nF1 _ _ _\n_ DU _ DL"),
+            vec!["advertencia (1, 0): el desvio apunta directamente a una pared"]
+        );
+    }
+
+    #[test]
+    fn test_enemigo_inalcanzable_y_bomba_inutil() {
+        assert_eq!(
+            mensajes("B1 _ F1\nW W W\nF2 _ B1"),
+            vec![
+                "advertencia (0, 0): la explosion de la bomba no alcanza ningun enemigo ni otra bomba",
+                "error (2, 0): el enemigo no es alcanzado por ninguna bomba",
+                "error (0, 2): el enemigo no es alcanzado por ninguna bomba",
+                "advertencia (2, 2): la explosion de la bomba no alcanza ningun enemigo ni otra bomba",
+            ]
+        );
+        assert_eq!(mensajes("B1 F1 _ F1p"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_enemigo_detras_de_una_piedra() {
+        assert_eq!(
+            mensajes("B2 R F1\nB1 W F1"),
+            vec![
+                "advertencia (2, 0): el enemigo solo es alcanzado si las explosiones destruyen piedras",
+                "error (2, 1): el enemigo no es alcanzado por ninguna bomba",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mapa_sin_bombas() {
+        assert_eq!(mensajes("_ _\n_ _"), vec!["error: el mapa no tiene bombas"]);
+        let mapa: Mapa = "F1 _".parse().unwrap();
+        let hallazgos = validar(&mapa);
+        assert_eq!(hallazgos.len(), 2);
+        assert_eq!(hallazgos[1].coordenada, None);
+        assert_eq!(hallazgos[1].severidad, Severidad::Error);
+    }
+
+    #[test]
+    fn test_mas_de_un_jugador() {
+        assert_eq!(
+            mensajes("J B1 F1\n_ J _"),
+            vec!["error (1, 1): el mapa tiene mas de un jugador"]
+        );
+        assert_eq!(mensajes("J B1 F1"), Vec::<String>::new());
+    }
+}
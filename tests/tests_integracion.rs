@@ -1,3 +1,4 @@
+use bomberman::turno::ModoResolucion;
 use bomberman::{bomberman_game, turno};
 
 #[test]
@@ -26,3 +27,41 @@ fn test_bomberman_mapa3() {
 
     assert_eq!(mapa, resultado_deseado);
 }
+
+#[test]
+fn test_bomberman_mapa4_rectangular() {
+    let mut mapa = bomberman_game::transformar_a_mapa("mapas/mapa_4.txt").unwrap();
+    let _ = turno::jugar_turno(&mut mapa, 0, 2);
+    let resultado_deseado = bomberman_game::transformar_a_mapa("mapas/mapa_4_deseado.txt").unwrap();
+
+    assert_eq!(mapa.ancho, 15);
+    assert_eq!(mapa.alto, 7);
+    assert_eq!(mapa, resultado_deseado);
+}
+
+#[test]
+fn test_bomberman_mapa5_desvios_en_los_bordes() {
+    let mut mapa = bomberman_game::transformar_a_mapa("mapas/mapa_5.txt").unwrap();
+    let _ = turno::jugar_turno(&mut mapa, 2, 2);
+    let resultado_deseado = bomberman_game::transformar_a_mapa("mapas/mapa_5_deseado.txt").unwrap();
+
+    assert_eq!(mapa, resultado_deseado);
+}
+
+#[test]
+fn test_bomberman_modos_de_resolucion_mismo_resultado() {
+    let casos = [
+        ("mapas/mapa_1.txt", 0, 0),
+        ("mapas/mapa_2.txt", 2, 4),
+        ("mapas/mapa_3.txt", 0, 4),
+        ("mapas/mapa_4.txt", 0, 2),
+        ("mapas/mapa_5.txt", 2, 2),
+    ];
+    for (path, x, y) in casos {
+        let mut profundidad = bomberman_game::transformar_a_mapa(path).unwrap();
+        let mut ondas = profundidad.clone();
+        turno::jugar_turno_con_modo(&mut profundidad, x, y, ModoResolucion::Profundidad).unwrap();
+        turno::jugar_turno_con_modo(&mut ondas, x, y, ModoResolucion::Ondas).unwrap();
+        assert_eq!(profundidad, ondas, "{}", path);
+    }
+}